@@ -249,7 +249,7 @@ pub fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
     ///
     /// Suppress lints whenever the suggested change would cause breakage for other crates.
     (avoid_breaking_exported_api: bool = true),
-    /// Lint: MANUAL_SPLIT_ONCE, MANUAL_STR_REPEAT, CLONED_INSTEAD_OF_COPIED, REDUNDANT_FIELD_NAMES, OPTION_MAP_UNWRAP_OR, REDUNDANT_STATIC_LIFETIMES, FILTER_MAP_NEXT, CHECKED_CONVERSIONS, MANUAL_RANGE_CONTAINS, USE_SELF, MEM_REPLACE_WITH_DEFAULT, MANUAL_NON_EXHAUSTIVE, OPTION_AS_REF_DEREF, MAP_UNWRAP_OR, MATCH_LIKE_MATCHES_MACRO, MANUAL_STRIP, MISSING_CONST_FOR_FN, UNNESTED_OR_PATTERNS, FROM_OVER_INTO, PTR_AS_PTR, IF_THEN_SOME_ELSE_NONE, APPROX_CONSTANT, DEPRECATED_CFG_ATTR, INDEX_REFUTABLE_SLICE, MAP_CLONE, BORROW_AS_PTR, MANUAL_BITS, ERR_EXPECT, CAST_ABS_TO_UNSIGNED, UNINLINED_FORMAT_ARGS, MANUAL_CLAMP, MANUAL_LET_ELSE, UNCHECKED_DURATION_SUBTRACTION, COLLAPSIBLE_STR_REPLACE, SEEK_FROM_CURRENT, SEEK_REWIND, UNNECESSARY_LAZY_EVALUATIONS, TRANSMUTE_PTR_TO_REF, ALMOST_COMPLETE_RANGE, NEEDLESS_BORROW, DERIVABLE_IMPLS, MANUAL_IS_ASCII_CHECK, MANUAL_REM_EUCLID, MANUAL_RETAIN, TYPE_REPETITION_IN_BOUNDS, TUPLE_ARRAY_CONVERSIONS, MANUAL_TRY_FOLD, MANUAL_HASH_ONE, ITER_KV_MAP.
+    /// Lint: MANUAL_SPLIT_ONCE, MANUAL_STR_REPEAT, CLONED_INSTEAD_OF_COPIED, REDUNDANT_FIELD_NAMES, OPTION_MAP_UNWRAP_OR, REDUNDANT_STATIC_LIFETIMES, FILTER_MAP_NEXT, CHECKED_CONVERSIONS, MANUAL_RANGE_CONTAINS, USE_SELF, MEM_REPLACE_WITH_DEFAULT, MANUAL_NON_EXHAUSTIVE, OPTION_AS_REF_DEREF, MAP_UNWRAP_OR, MATCH_LIKE_MATCHES_MACRO, MANUAL_STRIP, MISSING_CONST_FOR_FN, UNNESTED_OR_PATTERNS, FROM_OVER_INTO, PTR_AS_PTR, IF_THEN_SOME_ELSE_NONE, APPROX_CONSTANT, DEPRECATED_CFG_ATTR, INDEX_REFUTABLE_SLICE, MAP_CLONE, BORROW_AS_PTR, MANUAL_BITS, ERR_EXPECT, CAST_ABS_TO_UNSIGNED, UNINLINED_FORMAT_ARGS, MANUAL_CLAMP, MANUAL_LET_ELSE, UNCHECKED_DURATION_SUBTRACTION, COLLAPSIBLE_STR_REPLACE, SEEK_FROM_CURRENT, SEEK_REWIND, UNNECESSARY_LAZY_EVALUATIONS, TRANSMUTE_PTR_TO_REF, ALMOST_COMPLETE_RANGE, NEEDLESS_BORROW, DERIVABLE_IMPLS, MANUAL_IS_ASCII_CHECK, MANUAL_REM_EUCLID, MANUAL_RETAIN, TYPE_REPETITION_IN_BOUNDS, TUPLE_ARRAY_CONVERSIONS, MANUAL_TRY_FOLD, MANUAL_HASH_ONE, ITER_KV_MAP, EXPLICIT_DEREF_METHODS.
     ///
     /// The minimum rust version that the project supports. Defaults to the `rust-version` field in `Cargo.toml`
     #[default_text = ""]
@@ -552,6 +552,47 @@ pub fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
     /// Lint "public" fields in a struct that are prefixed with an underscore based on their
     /// exported visibility, or whether they are marked as "pub".
     (pub_underscore_fields_behavior: PubUnderscoreFieldsBehaviour = PubUnderscoreFieldsBehaviour::PublicallyExported),
+    /// Lint: NEEDLESS_BORROW.
+    ///
+    /// Whether to lint borrows when removing them requires wrapping the remaining expression in
+    /// parentheses. e.g. `(&&x).foo()` would become `(&x).foo()`
+    (needless_borrow_allow_parens: bool = true),
+    /// Lint: EXPLICIT_DEREF_METHODS.
+    ///
+    /// A list of types whose explicit `deref` and `deref_mut` calls won't be linted, written as
+    /// fully qualified paths.
+    (explicit_deref_methods_ignore: Vec<String> = Vec::new()),
+    /// Lint: EXPLICIT_DEREF_METHODS.
+    ///
+    /// Whether to lint explicit `deref` calls. Together with `explicit-deref-methods-check-deref-mut`
+    /// this lints calls of both methods, only `deref`, only `deref_mut`, or neither of them.
+    (explicit_deref_methods_check_deref: bool = true),
+    /// Lint: EXPLICIT_DEREF_METHODS.
+    ///
+    /// Whether to lint explicit `deref_mut` calls. e.g. setting only this one to `false` keeps
+    /// `x.deref_mut()` quiet while `x.deref()` is still linted.
+    (explicit_deref_methods_check_deref_mut: bool = true),
+    /// Lint: EXPLICIT_AUTO_DEREF, NEEDLESS_BORROW.
+    ///
+    /// A list of functions whose arguments are treated as coercion sites, even when the parameter's
+    /// type is generic, written as fully qualified paths.
+    (needless_borrow_coercion_fns: Vec<String> = Vec::new()),
+    /// Lint: EXPLICIT_AUTO_DEREF, EXPLICIT_DEREF_METHODS, NEEDLESS_BORROW, REF_BINDING_TO_REFERENCE.
+    ///
+    /// Whether to only attach suggestions which can be applied automatically. Any other suggestion
+    /// is replaced with a note.
+    (deref_suggestions_machine_applicable_only: bool = false),
+    /// Lint: EXPLICIT_AUTO_DEREF, EXPLICIT_DEREF_METHODS, NEEDLESS_BORROW, REDUNDANT_REBORROW.
+    ///
+    /// The maximum number of consecutive reference operations, e.g. `&` and `*`, an expression can
+    /// contain for a replacement to be suggested. Longer chains are still linted, but without a
+    /// suggestion.
+    (max_suggested_deref_chain_length: u64 = 16),
+    /// Lint: EXPLICIT_AUTO_DEREF.
+    ///
+    /// Whether to lint full-range slicing which auto-deref would do. e.g. `&v[..]` where
+    /// `v: Vec<u8>` is passed as `&[u8]`.
+    (explicit_auto_deref_full_slices: bool = false),
 }
 
 /// Search for the configuration file.
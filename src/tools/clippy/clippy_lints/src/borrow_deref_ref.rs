@@ -1,14 +1,19 @@
+use crate::dereference::{move_closure_captured_reborrow, NEEDLESS_BORROW};
 use crate::reference::DEREF_ADDROF;
 use clippy_utils::diagnostics::span_lint_and_then;
-use clippy_utils::source::snippet_opt;
+use clippy_utils::source::{snippet_opt, snippet_with_context, walk_span_to_context};
 use clippy_utils::ty::implements_trait;
-use clippy_utils::{get_parent_expr, is_from_proc_macro, is_lint_allowed};
+use clippy_utils::{
+    get_parent_expr, get_parent_node, is_from_proc_macro, is_lint_allowed, is_trait_method, path_to_local_id,
+};
 use rustc_errors::Applicability;
-use rustc_hir::{ExprKind, UnOp};
+use rustc_hir::{Closure, Expr, ExprKind, Local, Node, PatKind, UnOp};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::mir::Mutability;
 use rustc_middle::ty;
+use rustc_middle::ty::adjustment::{Adjust, AutoBorrow, AutoBorrowMutability};
 use rustc_session::declare_lint_pass;
+use rustc_span::{sym, Span};
 
 declare_clippy_lint! {
     /// ### What it does
@@ -48,13 +53,14 @@
 declare_lint_pass!(BorrowDerefRef => [BORROW_DEREF_REF]);
 
 impl<'tcx> LateLintPass<'tcx> for BorrowDerefRef {
-    fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &rustc_hir::Expr<'tcx>) {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &Expr<'tcx>) {
         if !e.span.from_expansion()
             && let ExprKind::AddrOf(_, Mutability::Not, addrof_target) = e.kind
-            && !addrof_target.span.from_expansion()
+            // Only the operand may come from a macro. e.g. `&*some_macro!()`
+            && addrof_target.span.eq_ctxt(e.span)
             && let ExprKind::Unary(UnOp::Deref, deref_target) = addrof_target.kind
-            && !deref_target.span.from_expansion()
-            && !matches!(deref_target.kind, ExprKind::Unary(UnOp::Deref, ..))
+            && let copied_span = iter_map_copied_span(cx, e, deref_target)
+            && (!matches!(deref_target.kind, ExprKind::Unary(UnOp::Deref, ..)) || copied_span.is_some())
             && let ref_ty = cx.typeck_results().expr_ty(deref_target)
             && let ty::Ref(_, inner_ty, Mutability::Not) = ref_ty.kind()
         {
@@ -73,8 +79,48 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &rustc_hir::Expr<'tcx>) {
                 {
                     return;
                 }
+
+                // `(&*x).f()` may mutably borrow the temporary reference. Without it the method would
+                // need `x` to be mutable, and would then modify it. e.g. `(&*s).read(buf)`
+                if let ExprKind::MethodCall(_, recv, ..) = parent_expr.kind
+                    && recv.hir_id == e.hir_id
+                    && deref_target.is_syntactic_place_expr()
+                    && cx.typeck_results().expr_adjustments(e).iter().any(|a| {
+                        matches!(
+                            a.kind,
+                            Adjust::Borrow(AutoBorrow::Ref(_, AutoBorrowMutability::Mut { .. }))
+                        )
+                    })
+                {
+                    return;
+                }
+            }
+            // Without a type the intent of a discarded borrow is unclear. e.g. `let _ = &*x;`
+            if let Some(Node::Local(Local { pat, ty: None, .. })) = get_parent_node(cx.tcx, e.hir_id)
+                && let PatKind::Wild = pat.kind
+            {
+                return;
+            }
+            // `deref_addrof` already suggests `&x` for `&*&x`
+            if matches!(deref_target.kind, ExprKind::AddrOf(..))
+                && deref_target.span.eq_ctxt(e.span)
+                && !is_lint_allowed(cx, DEREF_ADDROF, addrof_target.hir_id)
+            {
+                return;
             }
-            if is_from_proc_macro(cx, e) {
+            // `needless_borrow` handles results of `move` closures
+            if !is_lint_allowed(cx, NEEDLESS_BORROW, e.hir_id) && move_closure_captured_reborrow(cx, e).is_some() {
+                return;
+            }
+            // A macro operand's expansion doesn't match the source, only check the operators.
+            if deref_target.span.from_expansion()
+                && let Some(callsite) = walk_span_to_context(deref_target.span, e.span.ctxt())
+            {
+                let ops = snippet_opt(cx, e.span.until(callsite)).unwrap_or_default();
+                if !ops.chars().filter(|c| !c.is_whitespace()).eq("&*".chars()) {
+                    return;
+                }
+            } else if is_from_proc_macro(cx, e) {
                 return;
             }
 
@@ -84,12 +130,12 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &rustc_hir::Expr<'tcx>) {
                 e.span,
                 "deref on an immutable reference",
                 |diag| {
-                    diag.span_suggestion(
-                        e.span,
-                        "if you would like to reborrow, try removing `&*`",
-                        snippet_opt(cx, deref_target.span).unwrap(),
-                        Applicability::MachineApplicable,
-                    );
+                    let mut app = Applicability::MachineApplicable;
+                    let snip = snippet_with_context(cx, deref_target.span, e.span.ctxt(), "..", &mut app).0;
+                    diag.span_suggestion(e.span, "if you would like to reborrow, try removing `&*`", &snip, app);
+                    if let Some(span) = copied_span {
+                        diag.span_suggestion(span, "or copy each reference", "copied()", app);
+                    }
 
                     // has deref trait -> give 2 help
                     // doesn't have deref trait -> give 1 help
@@ -102,7 +148,7 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &rustc_hir::Expr<'tcx>) {
                     diag.span_suggestion(
                         e.span,
                         "if you would like to deref, try using `&**`",
-                        format!("&**{}", &snippet_opt(cx, deref_target.span).unwrap()),
+                        format!("&**{snip}"),
                         Applicability::MaybeIncorrect,
                     );
                 },
@@ -110,3 +156,29 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &rustc_hir::Expr<'tcx>) {
         }
     }
 }
+
+/// Checks if the expression is the body of a closure passed to `Iterator::map` which only removes
+/// one reference from each item. Returns the span of the `map` call to replace with `copied()`.
+/// e.g. `iter.map(|x| &**x)` where `x: &&T`
+fn iter_map_copied_span(cx: &LateContext<'_>, e: &Expr<'_>, deref_target: &Expr<'_>) -> Option<Span> {
+    if let ExprKind::Unary(UnOp::Deref, item) = deref_target.kind
+        && let ty::Ref(_, _, Mutability::Not) = cx.typeck_results().expr_ty(item).kind()
+        && let Some(closure) = get_parent_expr(cx, e)
+        && let ExprKind::Closure(&Closure { body, .. }) = closure.kind
+        && let body = cx.tcx.hir().body(body)
+        && body.value.hir_id == e.hir_id
+        && let [param] = body.params
+        && let PatKind::Binding(_, id, _, None) = param.pat.kind
+        && path_to_local_id(item, id)
+        && let Some(call) = get_parent_expr(cx, closure)
+        && let ExprKind::MethodCall(name, _, [arg], _) = call.kind
+        && name.ident.name == sym::map
+        && arg.hir_id == closure.hir_id
+        && call.span.eq_ctxt(e.span)
+        && is_trait_method(cx, call, sym::Iterator)
+    {
+        Some(name.ident.span.with_hi(call.span.hi()))
+    } else {
+        None
+    }
+}
@@ -121,6 +121,7 @@
     crate::dereference::EXPLICIT_AUTO_DEREF_INFO,
     crate::dereference::EXPLICIT_DEREF_METHODS_INFO,
     crate::dereference::NEEDLESS_BORROW_INFO,
+    crate::dereference::REDUNDANT_REBORROW_INFO,
     crate::dereference::REF_BINDING_TO_REFERENCE_INFO,
     crate::derivable_impls::DERIVABLE_IMPLS_INFO,
     crate::derive::DERIVED_HASH_WITH_MANUAL_EQ_INFO,
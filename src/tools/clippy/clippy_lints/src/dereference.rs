@@ -1,25 +1,42 @@
-use clippy_utils::diagnostics::{span_lint_and_sugg, span_lint_hir_and_then};
+use crate::borrow_deref_ref::BORROW_DEREF_REF;
+use crate::reference::DEREF_ADDROF;
+use clippy_config::msrvs::{self, Msrv};
+use clippy_config::Conf;
+use clippy_utils::diagnostics::{span_lint_and_then, span_lint_hir_and_then};
+use clippy_utils::macros::{find_assert_eq_args, find_format_arg_expr, find_format_args, root_macro_call_first_node};
 use clippy_utils::source::{snippet_with_applicability, snippet_with_context};
 use clippy_utils::sugg::has_enclosing_paren;
-use clippy_utils::ty::{implements_trait, is_manually_drop, peel_mid_ty_refs};
+use clippy_utils::ty::{
+    get_type_diagnostic_name, implements_trait, is_interior_mut_ty, is_manually_drop, is_type_diagnostic_item,
+    is_type_lang_item, needs_ordered_drop, peel_mid_ty_refs,
+};
+use clippy_utils::usage::local_used_after_expr;
 use clippy_utils::{
-    expr_use_ctxt, get_parent_expr, get_parent_node, is_lint_allowed, path_to_local, DefinedTy, ExprUseNode,
+    def_path_def_ids, expr_use_ctxt, get_parent_expr, get_parent_node, is_lint_allowed, path_def_id, path_to_local,
+    DefinedTy, ExprUseCtxt, ExprUseNode,
 };
-use core::mem;
+use core::{iter, mem, slice};
 use rustc_ast::util::parser::{PREC_POSTFIX, PREC_PREFIX};
-use rustc_data_structures::fx::FxIndexMap;
-use rustc_errors::Applicability;
+use rustc_ast::{FormatArgsPiece, FormatArgumentKind, FormatCount, FormatTrait};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
+use rustc_errors::{Applicability, Diagnostic};
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::DefId;
 use rustc_hir::intravisit::{walk_ty, Visitor};
 use rustc_hir::{
-    self as hir, BindingAnnotation, Body, BodyId, BorrowKind, Expr, ExprKind, HirId, MatchSource, Mutability, Node,
-    Pat, PatKind, Path, QPath, TyKind, UnOp,
+    self as hir, BinOpKind, BindingAnnotation, Body, BodyId, BorrowKind, CaptureBy, Closure, Expr, ExprKind, HirId,
+    LangItem, Local, MatchSource, Mutability, Node, Pat, PatKind, Path, QPath, TraitCandidate, TyKind, UnOp,
 };
-use rustc_lint::{LateContext, LateLintPass};
-use rustc_middle::ty::adjustment::{Adjust, Adjustment, AutoBorrow, AutoBorrowMutability};
-use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt, TypeVisitableExt, TypeckResults};
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_middle::hir::place::PlaceBase;
+use rustc_middle::ty::adjustment::{Adjust, Adjustment, AutoBorrow, AutoBorrowMutability, PointerCoercion};
+use rustc_middle::ty::fast_reject::{simplify_type, TreatParams};
+use rustc_middle::ty::{self, AssocKind, ParamEnv, Ty, TyCtxt, TypeVisitableExt, TypeckResults, UpvarCapture};
 use rustc_session::impl_lint_pass;
+use rustc_span::hygiene::{ExpnId, ExpnKind, MacroKind};
 use rustc_span::symbol::sym;
 use rustc_span::{Span, Symbol};
+use std::borrow::Cow;
 
 declare_clippy_lint! {
     /// ### What it does
@@ -57,7 +74,9 @@
 declare_clippy_lint! {
     /// ### What it does
     /// Checks for address of operations (`&`) that are going to
-    /// be dereferenced immediately by the compiler.
+    /// be dereferenced immediately by the compiler. This includes
+    /// the operands of `assert_eq!` and `assert_ne!`, which are
+    /// already borrowed by the macro.
     ///
     /// ### Why is this bad?
     /// Suggests that the receiver of the expression borrows
@@ -139,14 +158,44 @@
     "dereferencing when the compiler would automatically dereference"
 }
 
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `&*x` and `&mut *x` where `x` is already a reference of the same mutability to a
+    /// generic type parameter, and for `&mut *x` passed as an argument of a generic type where `x`
+    /// is already a mutable reference.
+    ///
+    /// ### Why is this bad?
+    /// The reborrow results in exactly the same type as `x`. Where the type is known the compiler
+    /// will reborrow `x` itself, so the explicit dereference and borrow have no effect. When passed
+    /// as a generic argument `x` is moved instead, which is only linted if `x` isn't used again.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// fn f<T: ?Sized>(x: &mut T) -> &mut T {
+    ///     &mut *x
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// fn f<T: ?Sized>(x: &mut T) -> &mut T {
+    ///     x
+    /// }
+    /// ```
+    #[clippy::version = "1.77.0"]
+    pub REDUNDANT_REBORROW,
+    complexity,
+    "reborrowing a reference to a type parameter as the same type"
+}
+
 impl_lint_pass!(Dereferencing<'_> => [
     EXPLICIT_DEREF_METHODS,
     NEEDLESS_BORROW,
     REF_BINDING_TO_REFERENCE,
     EXPLICIT_AUTO_DEREF,
+    REDUNDANT_REBORROW,
 ]);
 
-#[derive(Default)]
+#[expect(clippy::struct_excessive_bools)]
 pub struct Dereferencing<'tcx> {
     state: Option<(State, StateData<'tcx>)>,
 
@@ -160,6 +209,10 @@ pub struct Dereferencing<'tcx> {
     /// other.
     current_body: Option<BodyId>,
 
+    /// The outermost body generated by a derive macro which is currently being visited. Nothing is
+    /// linted inside of it.
+    derive_body: Option<BodyId>,
+
     /// The list of locals currently being checked by the lint.
     /// If the value is `None`, then the binding has been seen as a ref pattern, but is not linted.
     /// This is needed for or patterns where one of the branches can be linted, but another can not
@@ -167,6 +220,155 @@ pub struct Dereferencing<'tcx> {
     ///
     /// e.g. `m!(x) | Foo::Bar(ref x)`
     ref_locals: FxIndexMap<HirId, Option<RefPat>>,
+
+    /// A needless borrow whose removal was merged into the suggestion for the explicit deref
+    /// following it. Linted on its own if the deref has to stay.
+    merged_borrow: Option<DerefedBorrow>,
+
+    needless_borrow_allow_parens: bool,
+
+    explicit_deref_methods_ignore: Vec<String>,
+    /// Whether `deref` calls are linted by `EXPLICIT_DEREF_METHODS`.
+    check_deref_methods: bool,
+    /// Whether `deref_mut` calls are linted by `EXPLICIT_DEREF_METHODS`.
+    check_deref_mut_methods: bool,
+    needless_borrow_coercion_fns: Vec<String>,
+    /// Whether suggestions which can't be applied automatically are replaced with a note.
+    machine_applicable_only: bool,
+    /// The longest chain of reference operations a replacement is suggested for.
+    max_suggested_chain_length: u64,
+    /// Whether full-range slicing is linted by `EXPLICIT_AUTO_DEREF`.
+    check_full_slices: bool,
+    /// The resolved `DefId`s of the types in `explicit_deref_methods_ignore`.
+    ignored_deref_def_ids: FxHashSet<DefId>,
+    /// The resolved `DefId`s of the functions in `needless_borrow_coercion_fns`.
+    coercion_fn_def_ids: FxHashSet<DefId>,
+    /// The auto-deref stability of the parameter and return types already seen. Functions are
+    /// often called from many places with the same signature types.
+    stability_cache: StabilityCache<'tcx>,
+
+    msrv: Msrv,
+}
+
+impl<'tcx> Dereferencing<'tcx> {
+    pub fn new(conf: &Conf) -> Self {
+        Self {
+            state: None,
+            skip_expr: None,
+            current_body: None,
+            derive_body: None,
+            ref_locals: FxIndexMap::default(),
+            merged_borrow: None,
+            needless_borrow_allow_parens: conf.needless_borrow_allow_parens,
+            explicit_deref_methods_ignore: conf.explicit_deref_methods_ignore.clone(),
+            check_deref_methods: conf.explicit_deref_methods_check_deref,
+            check_deref_mut_methods: conf.explicit_deref_methods_check_deref_mut,
+            needless_borrow_coercion_fns: conf.needless_borrow_coercion_fns.clone(),
+            machine_applicable_only: conf.deref_suggestions_machine_applicable_only,
+            max_suggested_chain_length: conf.max_suggested_deref_chain_length,
+            check_full_slices: conf.explicit_auto_deref_full_slices,
+            ignored_deref_def_ids: FxHashSet::default(),
+            coercion_fn_def_ids: FxHashSet::default(),
+            stability_cache: StabilityCache::default(),
+            msrv: conf.msrv.clone(),
+        }
+    }
+
+    /// Checks if the span overlaps a usage of a ref binding which will be changed by the ref
+    /// binding's suggestion.
+    fn is_ref_binding_usage(&self, cx: &LateContext<'_>, span: Span) -> bool {
+        self.ref_locals.values().flatten().any(|pat| {
+            let lint = if pat.always_deref {
+                NEEDLESS_BORROW
+            } else {
+                REF_BINDING_TO_REFERENCE
+            };
+            !is_lint_allowed(cx, lint, pat.hir_id) && pat.replacements.iter().any(|&(sp, _)| sp.overlaps(span))
+        })
+    }
+
+    /// Parses full-range slicing as a reference operation when it's linted. e.g. `x[..]`
+    fn try_parse_full_slice(
+        &self,
+        cx: &LateContext<'tcx>,
+        expr: &'tcx Expr<'tcx>,
+    ) -> Option<(RefOp, &'tcx Expr<'tcx>)> {
+        if self.check_full_slices
+            && let ExprKind::Index(sub_expr, idx, _) = expr.kind
+            && is_full_slice_deref(cx, expr, sub_expr, idx)
+        {
+            Some((RefOp::Index, sub_expr))
+        } else {
+            None
+        }
+    }
+
+    /// Lints a chain of reference operations which has been fully seen.
+    fn report_chain(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        expr: &'tcx Expr<'_>,
+        state: State,
+        data: StateData<'tcx>,
+        typeck: &'tcx TypeckResults<'tcx>,
+    ) {
+        if let Some(borrow) = self.merged_borrow.take()
+            && let State::ExplicitDeref { mutability, .. } = state
+            && is_explicit_deref_required(cx, typeck, expr, &data, mutability)
+        {
+            report(cx, expr, State::DerefedBorrow(borrow), data, typeck, self);
+        } else {
+            report(cx, expr, state, data, typeck, self);
+        }
+    }
+
+    /// Checks if a needless borrow can be removed by the suggestion for the explicit deref which
+    /// follows it, rather than linting both separately. e.g. `(&*&x).len()` becomes `x.len()`
+    fn merges_borrow_into_deref(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        state: &DerefedBorrow,
+        data: &StateData<'tcx>,
+        deref_expr: &Expr<'_>,
+    ) -> bool {
+        state.for_field_access.is_none()
+            && state.stability.is_deref_stable()
+            && !state.keeps_mut_reborrow
+            && !is_lint_allowed(cx, EXPLICIT_AUTO_DEREF, deref_expr.hir_id)
+            && !is_lint_allowed(cx, NEEDLESS_BORROW, data.first_expr.hir_id)
+            && matches!(
+                needless_borrow_sugg(
+                    cx,
+                    data.first_expr,
+                    self.needless_borrow_allow_parens,
+                    &self.coercion_fn_def_ids,
+                    &mut self.stability_cache,
+                ),
+                Some((_, Applicability::MachineApplicable))
+            )
+    }
+
+    /// Checks if the receiver of a `deref` call has a type configured in
+    /// `explicit_deref_methods_ignore`.
+    fn is_ignored_deref_receiver(&self, ty: Ty<'_>) -> bool {
+        if let ty::Adt(adt, _) = ty.peel_refs().kind() {
+            self.ignored_deref_def_ids.contains(&adt.did())
+        } else {
+            false
+        }
+    }
+}
+
+/// Resolves the paths given in the configuration. A single name refers to an item at the root of
+/// the current crate.
+fn resolve_config_paths(cx: &LateContext<'_>, paths: &[String]) -> FxHashSet<DefId> {
+    paths
+        .iter()
+        .flat_map(|path| {
+            let segs: Vec<_> = path.split("::").collect();
+            def_path_def_ids(cx, &segs)
+        })
+        .collect()
 }
 
 #[derive(Debug)]
@@ -180,6 +382,8 @@ struct DerefedBorrow {
     msg: &'static str,
     stability: TyCoercionStability,
     for_field_access: Option<Symbol>,
+    /// Whether a mutable borrow has to be kept to avoid moving a mutable reference.
+    keeps_mut_reborrow: bool,
 }
 
 enum State {
@@ -194,23 +398,44 @@ enum State {
     DerefedBorrow(DerefedBorrow),
     ExplicitDeref {
         mutability: Option<Mutability>,
+        stability: TyCoercionStability,
     },
     ExplicitDerefField {
         name: Symbol,
-        derefs_manually_drop: bool,
     },
     Reborrow {
         mutability: Mutability,
+        stability: TyCoercionStability,
     },
     Borrow {
         mutability: Mutability,
+        stability: TyCoercionStability,
     },
 }
 
+/// Why a suggestion can't be applied automatically.
+#[derive(Clone, Copy)]
+pub(crate) enum MaybeIncorrectReason {
+    /// The target of the auto-deref depends on type inference.
+    InferredTarget,
+}
+impl MaybeIncorrectReason {
+    pub(crate) fn help(self) -> &'static str {
+        match self {
+            Self::InferredTarget => "the auto-deref target depends on type inference, which may resolve differently",
+        }
+    }
+}
+
 // A reference operation considered by this lint pass
 enum RefOp {
-    Method { mutbl: Mutability, is_ufcs: bool },
+    Method {
+        mutbl: Mutability,
+        is_ufcs: bool,
+    },
     Deref,
+    /// Full-range slicing of a container. e.g. `x[..]` where `x: Vec<_>`
+    Index,
     AddrOf(Mutability),
 }
 
@@ -228,8 +453,18 @@ struct RefPat {
 }
 
 impl<'tcx> LateLintPass<'tcx> for Dereferencing<'tcx> {
+    fn check_crate(&mut self, cx: &LateContext<'tcx>) {
+        self.ignored_deref_def_ids = resolve_config_paths(cx, &self.explicit_deref_methods_ignore);
+        self.coercion_fn_def_ids = resolve_config_paths(cx, &self.needless_borrow_coercion_fns);
+        self.stability_cache.clear();
+    }
+
     #[expect(clippy::too_many_lines)]
     fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
+        if self.derive_body.is_some() {
+            return;
+        }
+
         // Skip path expressions from deref calls. e.g. `Deref::deref(e)`
         if Some(expr.hir_id) == self.skip_expr.take() {
             return;
@@ -240,24 +475,93 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
         }
 
         // Stop processing sub expressions when a macro call is seen
-        if expr.span.from_expansion() {
+        if expr.span.from_expansion() || is_in_other_file(cx, expr) {
             if let Some((state, data)) = self.state.take() {
-                report(cx, expr, state, data, cx.typeck_results());
+                self.report_chain(cx, expr, state, data, cx.typeck_results());
             }
+            check_write_macro_args(cx, expr, self);
+            check_assert_eq_macro_args(cx, expr, self);
+            return;
+        }
+
+        self.check_index_base(cx, expr);
+        match expr.kind {
+            ExprKind::Let(let_expr) => self.check_scrutinee(cx, let_expr.init, slice::from_ref(&let_expr.pat)),
+            ExprKind::Match(scrutinee, arms, MatchSource::Normal) => {
+                self.check_scrutinee(cx, scrutinee, &arms.iter().map(|arm| arm.pat).collect::<Vec<_>>());
+            },
+            ExprKind::Binary(op, lhs, rhs) if matches!(op.node, BinOpKind::Eq | BinOpKind::Ne) => {
+                self.check_comparison(cx, expr, lhs, rhs);
+            },
+            _ => {},
+        }
+
+        if self.state.is_none()
+            && let Some(captured) = move_closure_captured_reborrow(cx, expr)
+        {
+            span_lint_hir_and_then(
+                cx,
+                NEEDLESS_BORROW,
+                expr.hir_id,
+                expr.span,
+                "this expression reborrows a captured reference which the closure can return directly",
+                |diag| {
+                    let mut app = Applicability::MachineApplicable;
+                    let snip = snippet_with_context(cx, captured.span, expr.span.ctxt(), "..", &mut app).0;
+                    suggest_or_note(
+                        diag,
+                        expr.span,
+                        "change this to",
+                        snip.into_owned(),
+                        app,
+                        self.machine_applicable_only,
+                    );
+                },
+            );
             return;
         }
 
         let typeck = cx.typeck_results();
-        let Some((kind, sub_expr)) = try_parse_ref_op(cx.tcx, typeck, expr) else {
+        let Some((kind, sub_expr)) = try_parse_ref_op(cx, typeck, expr).or_else(|| self.try_parse_full_slice(cx, expr))
+        else {
             // The whole chain of reference operations has been seen
-            if let Some((state, data)) = self.state.take() {
-                report(cx, expr, state, data, typeck);
+            if let Some((state, data)) = self.state.take()
+                // The ref binding's suggestion already changes this expression. Don't emit a
+                // second, overlapping suggestion.
+                && !self.is_ref_binding_usage(cx, data.first_expr.span)
+            {
+                self.report_chain(cx, expr, state, data, typeck);
             }
             return;
         };
 
         match (self.state.take(), kind) {
             (None, kind) => {
+                self.merged_borrow = None;
+                // Checked before the use context is computed as the lints are often allowed.
+                let skip = match kind {
+                    RefOp::Method { mutbl, is_ufcs } => {
+                        is_lint_allowed(cx, EXPLICIT_DEREF_METHODS, expr.hir_id)
+                            || !match mutbl {
+                                Mutability::Not => self.check_deref_methods,
+                                Mutability::Mut => self.check_deref_mut_methods,
+                            }
+                            // Allow explicit deref in method chains. e.g. `foo.deref().bar()`
+                            || (!is_ufcs && in_postfix_position(cx, expr))
+                            || self.is_ignored_deref_receiver(typeck.expr_ty(sub_expr))
+                    },
+                    // A `deref` call within the chain is covered by the lint of the whole chain, so the
+                    // chain still has to be consumed.
+                    RefOp::Deref | RefOp::Index | RefOp::AddrOf(_) => {
+                        [NEEDLESS_BORROW, EXPLICIT_AUTO_DEREF, REDUNDANT_REBORROW]
+                            .into_iter()
+                            .all(|lint| is_lint_allowed(cx, lint, expr.hir_id))
+                            && !ref_op_chain_has_method(cx, typeck, sub_expr)
+                    },
+                };
+                if skip {
+                    return;
+                }
                 let expr_ty = typeck.expr_ty(expr);
                 let use_cx = expr_use_ctxt(cx, expr);
                 let adjusted_ty = match &use_cx {
@@ -269,17 +573,14 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
                 };
 
                 match (use_cx, kind) {
-                    (Some(use_cx), RefOp::Deref) => {
+                    (Some(use_cx), RefOp::Deref | RefOp::Index) => {
                         let sub_ty = typeck.expr_ty(sub_expr);
                         if let ExprUseNode::FieldAccess(name) = use_cx.node
                             && !use_cx.moved_before_use
                             && !ty_contains_field(sub_ty, name.name)
                         {
                             self.state = Some((
-                                State::ExplicitDerefField {
-                                    name: name.name,
-                                    derefs_manually_drop: is_manually_drop(sub_ty),
-                                },
+                                State::ExplicitDerefField { name: name.name },
                                 StateData {
                                     first_expr: expr,
                                     adjusted_ty,
@@ -289,11 +590,20 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
                             // Linting method receivers would require verifying that name lookup
                             // would resolve the same way. This is complicated by trait methods.
                             && !use_cx.node.is_recv()
-                            && let Some(ty) = use_cx.node.defined_ty(cx)
-                            && TyCoercionStability::for_defined_ty(cx, ty, use_cx.node.is_return()).is_deref_stable()
+                            && let stability = use_cx_stability(
+                                cx,
+                                typeck,
+                                &use_cx,
+                                &self.coercion_fn_def_ids,
+                                &mut self.stability_cache,
+                            )
+                            && stability.is_deref_stable()
                         {
                             self.state = Some((
-                                State::ExplicitDeref { mutability: None },
+                                State::ExplicitDeref {
+                                    mutability: None,
+                                    stability,
+                                },
                                 StateData {
                                     first_expr: expr,
                                     adjusted_ty,
@@ -301,11 +611,7 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
                             ));
                         }
                     },
-                    (_, RefOp::Method { mutbl, is_ufcs })
-                        if !is_lint_allowed(cx, EXPLICIT_DEREF_METHODS, expr.hir_id)
-                            // Allow explicit deref in method chains. e.g. `foo.deref().bar()`
-                            && (is_ufcs || !in_postfix_position(cx, expr)) =>
-                    {
+                    (_, RefOp::Method { mutbl, is_ufcs }) => {
                         let ty_changed_count = usize::from(!deref_method_same_type(expr_ty, typeck.expr_ty(sub_expr)));
                         self.state = Some((
                             State::DerefMethod {
@@ -320,149 +626,18 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
                         ));
                     },
                     (Some(use_cx), RefOp::AddrOf(mutability)) => {
-                        // Find the number of times the borrow is auto-derefed.
-                        let mut iter = use_cx.adjustments.iter();
-                        let mut deref_count = 0usize;
-                        let next_adjust = loop {
-                            match iter.next() {
-                                Some(adjust) => {
-                                    if !matches!(adjust.kind, Adjust::Deref(_)) {
-                                        break Some(adjust);
-                                    } else if !adjust.target.is_ref() {
-                                        deref_count += 1;
-                                        break iter.next();
-                                    }
-                                    deref_count += 1;
-                                },
-                                None => break None,
-                            };
-                        };
-
-                        let stability = use_cx.node.defined_ty(cx).map_or(TyCoercionStability::None, |ty| {
-                            TyCoercionStability::for_defined_ty(cx, ty, use_cx.node.is_return())
-                        });
-                        let can_auto_borrow = match use_cx.node {
-                            ExprUseNode::FieldAccess(_)
-                                if !use_cx.moved_before_use && matches!(sub_expr.kind, ExprKind::Field(..)) =>
-                            {
-                                // `DerefMut` will not be automatically applied to `ManuallyDrop<_>`
-                                // field expressions when the base type is a union and the parent
-                                // expression is also a field access.
-                                //
-                                // e.g. `&mut x.y.z` where `x` is a union, and accessing `z` requires a
-                                // deref through `ManuallyDrop<_>` will not compile.
-                                !adjust_derefs_manually_drop(use_cx.adjustments, expr_ty)
-                            },
-                            ExprUseNode::Callee | ExprUseNode::FieldAccess(_) => true,
-                            ExprUseNode::MethodArg(hir_id, _, 0) if !use_cx.moved_before_use => {
-                                // Check for calls to trait methods where the trait is implemented
-                                // on a reference.
-                                // Two cases need to be handled:
-                                // * `self` methods on `&T` will never have auto-borrow
-                                // * `&self` methods on `&T` can have auto-borrow, but `&self` methods on `T` will take
-                                //   priority.
-                                if let Some(fn_id) = typeck.type_dependent_def_id(hir_id)
-                                    && let Some(trait_id) = cx.tcx.trait_of_item(fn_id)
-                                    && let arg_ty = cx
-                                        .tcx
-                                        .erase_regions(use_cx.adjustments.last().map_or(expr_ty, |a| a.target))
-                                    && let ty::Ref(_, sub_ty, _) = *arg_ty.kind()
-                                    && let args =
-                                        typeck.node_args_opt(hir_id).map(|args| &args[1..]).unwrap_or_default()
-                                    && let impl_ty =
-                                        if cx.tcx.fn_sig(fn_id).instantiate_identity().skip_binder().inputs()[0]
-                                            .is_ref()
-                                        {
-                                            // Trait methods taking `&self`
-                                            sub_ty
-                                        } else {
-                                            // Trait methods taking `self`
-                                            arg_ty
-                                        }
-                                    && impl_ty.is_ref()
-                                    && implements_trait(
-                                        cx,
-                                        impl_ty,
-                                        trait_id,
-                                        &args[..cx.tcx.generics_of(trait_id).params.len() - 1],
-                                    )
-                                {
-                                    false
-                                } else {
-                                    true
-                                }
-                            },
-                            _ => false,
-                        };
-
-                        let deref_msg =
-                            "this expression creates a reference which is immediately dereferenced by the compiler";
-                        let borrow_msg = "this expression borrows a value the compiler would automatically borrow";
-
-                        // Determine the required number of references before any can be removed. In all cases the
-                        // reference made by the current expression will be removed. After that there are four cases to
-                        // handle.
-                        //
-                        // 1. Auto-borrow will trigger in the current position, so no further references are required.
-                        // 2. Auto-deref ends at a reference, or the underlying type, so one extra needs to be left to
-                        //    handle the automatically inserted re-borrow.
-                        // 3. Auto-deref hits a user-defined `Deref` impl, so at least one reference needs to exist to
-                        //    start auto-deref.
-                        // 4. If the chain of non-user-defined derefs ends with a mutable re-borrow, and re-borrow
-                        //    adjustments will not be inserted automatically, then leave one further reference to avoid
-                        //    moving a mutable borrow. e.g.
-                        //
-                        //    ```rust
-                        //    fn foo<T>(x: &mut Option<&mut T>, y: &mut T) {
-                        //        let x = match x {
-                        //            // Removing the borrow will cause `x` to be moved
-                        //            Some(x) => &mut *x,
-                        //            None => y
-                        //        };
-                        //    }
-                        //    ```
-                        let (required_refs, msg) = if can_auto_borrow {
-                            (1, if deref_count == 1 { borrow_msg } else { deref_msg })
-                        } else if let Some(&Adjustment {
-                            kind: Adjust::Borrow(AutoBorrow::Ref(_, mutability)),
-                            ..
-                        }) = next_adjust
-                            && matches!(mutability, AutoBorrowMutability::Mut { .. })
-                            && !stability.is_reborrow_stable()
-                        {
-                            (3, deref_msg)
-                        } else {
-                            (2, deref_msg)
-                        };
-
-                        if deref_count >= required_refs {
-                            self.state = Some((
-                                State::DerefedBorrow(DerefedBorrow {
-                                    // One of the required refs is for the current borrow expression, the remaining ones
-                                    // can't be removed without breaking the code. See earlier comment.
-                                    count: deref_count - required_refs,
-                                    msg,
-                                    stability,
-                                    for_field_access: if let ExprUseNode::FieldAccess(name) = use_cx.node
-                                        && !use_cx.moved_before_use
-                                    {
-                                        Some(name.name)
-                                    } else {
-                                        None
-                                    },
-                                }),
-                                StateData {
-                                    first_expr: expr,
-                                    adjusted_ty: use_cx.adjustments.last().map_or(expr_ty, |a| a.target),
-                                },
-                            ));
-                        } else if stability.is_deref_stable()
-                            // Auto-deref doesn't combine with other adjustments
-                            && next_adjust.map_or(true, |a| matches!(a.kind, Adjust::Deref(_) | Adjust::Borrow(_)))
-                            && iter.all(|a| matches!(a.kind, Adjust::Deref(_) | Adjust::Borrow(_)))
-                        {
+                        if let Some(state) = addr_of_state(
+                            cx,
+                            typeck,
+                            expr,
+                            sub_expr,
+                            &use_cx,
+                            &self.coercion_fn_def_ids,
+                            &mut self.stability_cache,
+                            mutability,
+                        ) {
                             self.state = Some((
-                                State::Borrow { mutability },
+                                state,
                                 StateData {
                                     first_expr: expr,
                                     adjusted_ty: use_cx.adjustments.last().map_or(expr_ty, |a| a.target),
@@ -470,7 +645,7 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
                             ));
                         }
                     },
-                    (None, _) | (_, RefOp::Method { .. }) => (),
+                    (None, _) => (),
                 }
             },
             (
@@ -509,10 +684,17 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
             (Some((State::DerefedBorrow(state), data)), RefOp::AddrOf(mutability)) => {
                 let adjusted_ty = data.adjusted_ty;
                 let stability = state.stability;
-                report(cx, expr, State::DerefedBorrow(state), data, typeck);
+                if borrow_continues_to_explicit_deref(cx, typeck, sub_expr)
+                    && self.merges_borrow_into_deref(cx, &state, &data, expr)
+                {
+                    self.merged_borrow = Some(state);
+                    self.state = Some((State::Borrow { mutability, stability }, data));
+                    return;
+                }
+                report(cx, expr, State::DerefedBorrow(state), data, typeck, self);
                 if stability.is_deref_stable() {
                     self.state = Some((
-                        State::Borrow { mutability },
+                        State::Borrow { mutability, stability },
                         StateData {
                             first_expr: expr,
                             adjusted_ty,
@@ -520,30 +702,44 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
                     ));
                 }
             },
-            (Some((State::DerefedBorrow(state), data)), RefOp::Deref) => {
+            (Some((State::DerefedBorrow(state), data)), RefOp::Deref | RefOp::Index) => {
                 let adjusted_ty = data.adjusted_ty;
                 let stability = state.stability;
                 let for_field_access = state.for_field_access;
-                report(cx, expr, State::DerefedBorrow(state), data, typeck);
+                let parent = get_parent_expr(cx, expr);
+                if let Some(parent) = parent
+                    && self.merges_borrow_into_deref(cx, &state, &data, parent)
+                {
+                    self.merged_borrow = Some(state);
+                    self.state = Some((
+                        State::ExplicitDeref {
+                            mutability: None,
+                            stability,
+                        },
+                        data,
+                    ));
+                    return;
+                }
+                report(cx, expr, State::DerefedBorrow(state), data, typeck, self);
                 if let Some(name) = for_field_access
                     && let sub_expr_ty = typeck.expr_ty(sub_expr)
                     && !ty_contains_field(sub_expr_ty, name)
                 {
                     self.state = Some((
-                        State::ExplicitDerefField {
-                            name,
-                            derefs_manually_drop: is_manually_drop(sub_expr_ty),
-                        },
+                        State::ExplicitDerefField { name },
                         StateData {
                             first_expr: expr,
                             adjusted_ty,
                         },
                     ));
                 } else if stability.is_deref_stable()
-                    && let Some(parent) = get_parent_expr(cx, expr)
+                    && let Some(parent) = parent
                 {
                     self.state = Some((
-                        State::ExplicitDeref { mutability: None },
+                        State::ExplicitDeref {
+                            mutability: None,
+                            stability,
+                        },
                         StateData {
                             first_expr: parent,
                             adjusted_ty,
@@ -552,60 +748,64 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
                 }
             },
 
-            (Some((State::Borrow { mutability }, data)), RefOp::Deref) => {
+            (Some((State::Borrow { mutability, stability }, data)), RefOp::Deref | RefOp::Index) => {
                 if typeck.expr_ty(sub_expr).is_ref() {
-                    self.state = Some((State::Reborrow { mutability }, data));
+                    self.state = Some((State::Reborrow { mutability, stability }, data));
                 } else {
                     self.state = Some((
                         State::ExplicitDeref {
                             mutability: Some(mutability),
+                            stability,
                         },
                         data,
                     ));
                 }
             },
-            (Some((State::Reborrow { mutability }, data)), RefOp::Deref) => {
+            (
+                Some((State::Reborrow { mutability, stability }, data)),
+                RefOp::Deref | RefOp::Index | RefOp::AddrOf(_),
+            ) => {
+                // A borrow directly under a deref cancels out. e.g. `&*&*x`
                 self.state = Some((
                     State::ExplicitDeref {
                         mutability: Some(mutability),
+                        stability,
                     },
                     data,
                 ));
             },
-            (state @ Some((State::ExplicitDeref { .. }, _)), RefOp::Deref) => {
+            (state @ Some((State::ExplicitDeref { .. }, _)), RefOp::Deref | RefOp::Index | RefOp::AddrOf(_)) => {
                 self.state = state;
             },
-            (
-                Some((
-                    State::ExplicitDerefField {
-                        name,
-                        derefs_manually_drop,
-                    },
-                    data,
-                )),
-                RefOp::Deref,
-            ) if let sub_expr_ty = typeck.expr_ty(sub_expr)
-                && !ty_contains_field(sub_expr_ty, name) =>
+            (Some((State::ExplicitDerefField { name }, data)), RefOp::Deref | RefOp::Index)
+                if !ty_contains_field(typeck.expr_ty(sub_expr), name) =>
             {
-                self.state = Some((
-                    State::ExplicitDerefField {
-                        name,
-                        derefs_manually_drop: derefs_manually_drop || is_manually_drop(sub_expr_ty),
-                    },
-                    data,
-                ));
+                self.state = Some((State::ExplicitDerefField { name }, data));
             },
 
-            (Some((state, data)), _) => report(cx, expr, state, data, typeck),
+            (Some((state, data)), _) => self.report_chain(cx, expr, state, data, typeck),
+        }
+    }
+
+    fn check_local(&mut self, cx: &LateContext<'tcx>, local: &'tcx Local<'_>) {
+        if self.derive_body.is_none()
+            && local.ty.is_none()
+            && let Some(init) = local.init
+        {
+            self.check_scrutinee(cx, init, slice::from_ref(&local.pat));
         }
     }
 
     fn check_pat(&mut self, cx: &LateContext<'tcx>, pat: &'tcx Pat<'_>) {
+        if self.derive_body.is_some() {
+            return;
+        }
+
         if let PatKind::Binding(BindingAnnotation::REF, id, name, _) = pat.kind {
             if let Some(opt_prev_pat) = self.ref_locals.get_mut(&id) {
                 // This binding id has been seen before. Add this pattern to the list of changes.
                 if let Some(prev_pat) = opt_prev_pat {
-                    if pat.span.from_expansion() {
+                    if pat.span.from_expansion() || !name.span.eq_ctxt(pat.span) {
                         // Doesn't match the context of the previous pattern. Can't lint here.
                         *opt_prev_pat = None;
                     } else {
@@ -622,10 +822,18 @@ fn check_pat(&mut self, cx: &LateContext<'tcx>, pat: &'tcx Pat<'_>) {
             }
 
             if !pat.span.from_expansion()
+                && (!is_lint_allowed(cx, NEEDLESS_BORROW, pat.hir_id)
+                    || !is_lint_allowed(cx, REF_BINDING_TO_REFERENCE, pat.hir_id))
                 && let ty::Ref(_, tam, _) = *cx.typeck_results().pat_ty(pat).kind()
                 // only lint immutable refs, because borrowed `&mut T` cannot be moved out
                 && let ty::Ref(_, _, Mutability::Not) = *tam.kind()
             {
+                if !name.span.eq_ctxt(pat.span) {
+                    // The name was created by a different expansion than the pattern, e.g. by a proc
+                    // macro. It can't be replaced in the pattern's context.
+                    self.ref_locals.insert(id, None);
+                    return;
+                }
                 let mut app = Applicability::MachineApplicable;
                 let snip = snippet_with_context(cx, name.span, pat.span.ctxt(), "..", &mut app).0;
                 self.current_body = self.current_body.or(cx.enclosing_body);
@@ -643,7 +851,17 @@ fn check_pat(&mut self, cx: &LateContext<'tcx>, pat: &'tcx Pat<'_>) {
         }
     }
 
+    fn check_body(&mut self, cx: &LateContext<'tcx>, body: &'tcx Body<'_>) {
+        if self.derive_body.is_none() && is_derive_body(cx, body) {
+            self.derive_body = Some(body.id());
+        }
+    }
+
     fn check_body_post(&mut self, cx: &LateContext<'tcx>, body: &'tcx Body<'_>) {
+        if Some(body.id()) == self.derive_body {
+            self.derive_body = None;
+            return;
+        }
         if Some(body.id()) == self.current_body {
             for pat in self.ref_locals.drain(..).filter_map(|(_, x)| x) {
                 let replacements = pat.replacements;
@@ -660,89 +878,287 @@ fn check_body_post(&mut self, cx: &LateContext<'tcx>, body: &'tcx Body<'_>) {
                     pat.spans,
                     "this pattern creates a reference to a reference",
                     |diag| {
-                        diag.multipart_suggestion("try", replacements, app);
+                        if self.machine_applicable_only && app != Applicability::MachineApplicable {
+                            note_omitted_suggestion(diag);
+                        } else {
+                            diag.multipart_suggestion("try", replacements, app);
+                        }
                     },
                 );
             }
             self.current_body = None;
         }
     }
+
+    extract_msrv_attr!(LateContext);
 }
 
-fn try_parse_ref_op<'tcx>(
-    tcx: TyCtxt<'tcx>,
-    typeck: &'tcx TypeckResults<'_>,
-    expr: &'tcx Expr<'_>,
-) -> Option<(RefOp, &'tcx Expr<'tcx>)> {
-    let (is_ufcs, def_id, arg) = match expr.kind {
-        ExprKind::MethodCall(_, arg, [], _) => (false, typeck.type_dependent_def_id(expr.hir_id)?, arg),
-        ExprKind::Call(
-            Expr {
-                kind: ExprKind::Path(path),
-                hir_id,
-                ..
+// Determines the state for a borrow expression before any of its sub-expressions are checked.
+#[expect(clippy::too_many_arguments, clippy::too_many_lines)]
+fn addr_of_state<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+    sub_expr: &'tcx Expr<'tcx>,
+    use_cx: &ExprUseCtxt<'tcx>,
+    coercion_fns: &FxHashSet<DefId>,
+    stability_cache: &mut StabilityCache<'tcx>,
+    mutability: Mutability,
+) -> Option<State> {
+    let expr_ty = typeck.expr_ty(expr);
+    // Only a reborrow is inserted for a generic argument.
+    let is_reborrowed = use_cx.adjustments.last().map_or(true, |a| {
+        cx.tcx.erase_regions(a.target) == cx.tcx.erase_regions(expr_ty)
+    });
+    if is_reborrowed
+        && is_coercion_fn_arg(cx, typeck, use_cx, coercion_fns)
+        && let Some(state) = coercion_fn_arg_borrow_state(typeck, sub_expr, mutability)
+    {
+        return Some(state);
+    }
+    // Find the number of times the borrow is auto-derefed.
+    let mut iter = use_cx.adjustments.iter();
+    let mut deref_count = 0usize;
+    let next_adjust = loop {
+        match iter.next() {
+            Some(adjust) => {
+                if !matches!(adjust.kind, Adjust::Deref(_)) {
+                    break Some(adjust);
+                } else if !adjust.target.is_ref() {
+                    deref_count += 1;
+                    break iter.next();
+                }
+                deref_count += 1;
             },
-            [arg],
-        ) => (true, typeck.qpath_res(path, *hir_id).opt_def_id()?, arg),
-        ExprKind::Unary(UnOp::Deref, sub_expr) if !typeck.expr_ty(sub_expr).is_unsafe_ptr() => {
-            return Some((RefOp::Deref, sub_expr));
+            None => break None,
+        };
+    };
+
+    let stability = use_cx_stability(cx, typeck, use_cx, coercion_fns, stability_cache);
+    let can_auto_borrow = match use_cx.node {
+        ExprUseNode::FieldAccess(_) if !use_cx.moved_before_use && matches!(sub_expr.kind, ExprKind::Field(..)) => {
+            // `DerefMut` will not be automatically applied to `ManuallyDrop<_>`
+            // field expressions when the base type is a union and the parent
+            // expression is also a field access.
+            //
+            // e.g. `&mut x.y.z` where `x` is a union, and accessing `z` requires a
+            // deref through `ManuallyDrop<_>` will not compile.
+            !adjust_derefs_manually_drop(use_cx.adjustments, expr_ty)
         },
-        ExprKind::AddrOf(BorrowKind::Ref, mutability, sub_expr) => return Some((RefOp::AddrOf(mutability), sub_expr)),
-        _ => return None,
+        ExprUseNode::Callee | ExprUseNode::FieldAccess(_) => true,
+        ExprUseNode::MethodArg(hir_id, _, 0) if !use_cx.moved_before_use => {
+            // Check for calls to trait methods where the trait is implemented
+            // on a reference.
+            // Two cases need to be handled:
+            // * `self` methods on `&T` will never have auto-borrow
+            // * `&self` methods on `&T` can have auto-borrow, but `&self` methods on `T` will take priority.
+            if let Some(fn_id) = typeck.type_dependent_def_id(hir_id)
+                && let Some(trait_id) = cx.tcx.trait_of_item(fn_id)
+                && let arg_ty = cx
+                    .tcx
+                    .erase_regions(use_cx.adjustments.last().map_or(expr_ty, |a| a.target))
+                && let ty::Ref(_, sub_ty, _) = *arg_ty.kind()
+                && let args = typeck.node_args_opt(hir_id).map(|args| &args[1..]).unwrap_or_default()
+                && let impl_ty = if cx.tcx.fn_sig(fn_id).instantiate_identity().skip_binder().inputs()[0].is_ref() {
+                    // Trait methods taking `&self`
+                    sub_ty
+                } else {
+                    // Trait methods taking `self`
+                    arg_ty
+                }
+                && impl_ty.is_ref()
+                && implements_trait(
+                    cx,
+                    impl_ty,
+                    trait_id,
+                    &args[..cx.tcx.generics_of(trait_id).params.len() - 1],
+                )
+            {
+                false
+            } else {
+                true
+            }
+        },
+        _ => false,
     };
-    if tcx.is_diagnostic_item(sym::deref_method, def_id) {
-        Some((
-            RefOp::Method {
-                mutbl: Mutability::Not,
-                is_ufcs,
-            },
-            arg,
-        ))
-    } else if tcx.trait_of_item(def_id)? == tcx.lang_items().deref_mut_trait()? {
-        Some((
-            RefOp::Method {
-                mutbl: Mutability::Mut,
-                is_ufcs,
+
+    // The reference is coerced to an unsized type. e.g. `&&x` passed as `&dyn Trait`
+    // Removing a borrow would change which type is unsized, or stop the coercion from applying.
+    if !can_auto_borrow
+        && use_cx
+            .adjustments
+            .iter()
+            .any(|a| matches!(a.kind, Adjust::Pointer(PointerCoercion::Unsize)))
+    {
+        return None;
+    }
+
+    let deref_msg = "this expression creates a reference which is immediately dereferenced by the compiler";
+    let borrow_msg = "this expression borrows a value the compiler would automatically borrow";
+
+    // Determine the required number of references before any can be removed. In all cases the
+    // reference made by the current expression will be removed. After that there are four cases to
+    // handle.
+    //
+    // 1. Auto-borrow will trigger in the current position, so no further references are required.
+    // 2. Auto-deref ends at a reference, or the underlying type, so one extra needs to be left to
+    //    handle the automatically inserted re-borrow.
+    // 3. Auto-deref hits a user-defined `Deref` impl, so at least one reference needs to exist to start
+    //    auto-deref.
+    // 4. If the chain of non-user-defined derefs ends with a mutable re-borrow, and re-borrow
+    //    adjustments will not be inserted automatically, then leave one further reference to avoid
+    //    moving a mutable borrow. e.g.
+    //
+    //    ```rust
+    //    fn foo<T>(x: &mut Option<&mut T>, y: &mut T) {
+    //        let x = match x {
+    //            // Removing the borrow will cause `x` to be moved
+    //            Some(x) => &mut *x,
+    //            None => y
+    //        };
+    //    }
+    //    ```
+    let (required_refs, msg) = if can_auto_borrow {
+        (1, if deref_count == 1 { borrow_msg } else { deref_msg })
+    } else if let Some(&Adjustment {
+        kind: Adjust::Borrow(AutoBorrow::Ref(_, mutability)),
+        ..
+    }) = next_adjust
+        && matches!(mutability, AutoBorrowMutability::Mut { .. })
+        && !stability.is_reborrow_stable()
+    {
+        (3, deref_msg)
+    } else {
+        (2, deref_msg)
+    };
+
+    if deref_count >= required_refs {
+        Some(State::DerefedBorrow(DerefedBorrow {
+            // One of the required refs is for the current borrow expression, the remaining ones
+            // can't be removed without breaking the code. See earlier comment.
+            count: deref_count - required_refs,
+            msg,
+            stability,
+            for_field_access: if let ExprUseNode::FieldAccess(name) = use_cx.node
+                && !use_cx.moved_before_use
+            {
+                Some(name.name)
+            } else {
+                None
             },
-            arg,
-        ))
+            keeps_mut_reborrow: required_refs == 3,
+        }))
+    } else if (stability.is_deref_stable()
+        // Auto-deref doesn't combine with other adjustments
+        && next_adjust.map_or(true, |a| matches!(a.kind, Adjust::Deref(_) | Adjust::Borrow(_)))
+        && iter.all(|a| matches!(a.kind, Adjust::Deref(_) | Adjust::Borrow(_))))
+        // e.g. `v.push(&mut *x)`
+        || (mutability == Mutability::Mut && is_moved_generic_reborrow(cx, typeck, expr, sub_expr, use_cx))
+    {
+        Some(State::Borrow { mutability, stability })
     } else {
         None
     }
 }
 
-// Checks if the adjustments contains a deref of `ManuallyDrop<_>`
-fn adjust_derefs_manually_drop<'tcx>(adjustments: &'tcx [Adjustment<'tcx>], mut ty: Ty<'tcx>) -> bool {
-    adjustments.iter().any(|a| {
-        let ty = mem::replace(&mut ty, a.target);
-        matches!(a.kind, Adjust::Deref(Some(ref op)) if op.mutbl == Mutability::Mut) && is_manually_drop(ty)
-    })
+/// Checks if the value is an argument of one of the functions configured in
+/// `needless_borrow_coercion_fns`.
+fn is_coercion_fn_arg<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &TypeckResults<'tcx>,
+    use_cx: &ExprUseCtxt<'tcx>,
+    coercion_fns: &FxHashSet<DefId>,
+) -> bool {
+    let callee = match use_cx.node {
+        ExprUseNode::FnArg(func, _) => path_def_id(cx, func),
+        ExprUseNode::MethodArg(hir_id, _, i) if i != 0 => typeck.type_dependent_def_id(hir_id),
+        _ => None,
+    };
+    callee.is_some_and(|id| coercion_fns.contains(&id))
 }
 
-// Checks whether the type for a deref call actually changed the type, not just the mutability of
-// the reference.
-fn deref_method_same_type<'tcx>(result_ty: Ty<'tcx>, arg_ty: Ty<'tcx>) -> bool {
-    match (result_ty.kind(), arg_ty.kind()) {
-        (ty::Ref(_, result_ty, _), ty::Ref(_, arg_ty, _)) => result_ty == arg_ty,
+/// Gets the stability of auto-deref where the value is used. The arguments of the configured
+/// coercion functions are treated as coercion sites even though their type is generic.
+fn use_cx_stability<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &TypeckResults<'tcx>,
+    use_cx: &ExprUseCtxt<'tcx>,
+    coercion_fns: &FxHashSet<DefId>,
+    stability_cache: &mut StabilityCache<'tcx>,
+) -> TyCoercionStability {
+    if is_coercion_fn_arg(cx, typeck, use_cx, coercion_fns) {
+        TyCoercionStability::DerefGeneric
+    } else {
+        TyCoercionStability::for_use_cx(cx, use_cx, stability_cache)
+    }
+}
 
-        // The result type for a deref method is always a reference
-        // Not matching the previous pattern means the argument type is not a reference
-        // This means that the type did change
+/// Determines the state for a borrow passed to a configured coercion function. The compiler
+/// doesn't adjust the argument as its type is generic, but a coercion would remove every borrow of
+/// a reference. e.g. `f(&&x)` where `x: T` becomes `f(&x)`
+fn coercion_fn_arg_borrow_state(
+    typeck: &TypeckResults<'_>,
+    sub_expr: &Expr<'_>,
+    mutability: Mutability,
+) -> Option<State> {
+    // A mutable borrow can only be removed if it borrows a mutable reference.
+    let is_removable = |e: &Expr<'_>| match *typeck.expr_ty(e).kind() {
+        ty::Ref(_, _, m) => mutability == Mutability::Not || m == Mutability::Mut,
         _ => false,
+    };
+    if !is_removable(sub_expr) {
+        return None;
+    }
+    let mut count = 0;
+    let mut e = sub_expr;
+    while let ExprKind::AddrOf(BorrowKind::Ref, _, inner) = e.kind
+        && inner.span.eq_ctxt(e.span)
+        && is_removable(inner)
+    {
+        count += 1;
+        e = inner;
     }
+    Some(State::DerefedBorrow(DerefedBorrow {
+        count,
+        msg: "this expression creates a reference which is immediately dereferenced by the compiler",
+        stability: TyCoercionStability::DerefGeneric,
+        for_field_access: None,
+        keeps_mut_reborrow: false,
+    }))
 }
 
-fn in_postfix_position<'tcx>(cx: &LateContext<'tcx>, e: &'tcx Expr<'tcx>) -> bool {
-    if let Some(parent) = get_parent_expr(cx, e)
-        && parent.span.eq_ctxt(e.span)
+/// Checks if `expr` is `&mut *x`, where `x` is a mutable reference, passed as an argument of a
+/// generic type. Removing the reborrow either has the compiler reborrow `x` instead, or moves `x`
+/// when it isn't used again.
+fn is_moved_generic_reborrow<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+    sub_expr: &'tcx Expr<'tcx>,
+    use_cx: &ExprUseCtxt<'tcx>,
+) -> bool {
+    if let ExprUseNode::FnArg(..) | ExprUseNode::MethodArg(..) = use_cx.node
+        && !use_cx.node.is_recv()
+        && let Some(DefinedTy::Mir(ty)) = use_cx.node.defined_ty(cx)
+        && let ty::Param(_) = *ty.value.skip_binder().kind()
+        && let ExprKind::Unary(UnOp::Deref, inner) = sub_expr.kind
+        && let Some(local) = path_to_local(inner)
+        && let ty::Ref(_, _, Mutability::Mut) = *typeck.expr_ty(inner).kind()
     {
-        match parent.kind {
-            ExprKind::Call(child, _) | ExprKind::MethodCall(_, child, _, _) | ExprKind::Index(child, _, _)
-                if child.hir_id == e.hir_id =>
-            {
-                true
-            },
-            ExprKind::Match(.., MatchSource::TryDesugar(_) | MatchSource::AwaitDesugar) | ExprKind::Field(_, _) => true,
+        match use_cx.adjustments {
+            // The argument type is already known to be a mutable reference, so the compiler reborrows.
+            [
+                Adjustment {
+                    kind: Adjust::Deref(None),
+                    ..
+                },
+                Adjustment {
+                    kind: Adjust::Borrow(AutoBorrow::Ref(_, AutoBorrowMutability::Mut { .. })),
+                    ..
+                },
+            ] => true,
+            // Otherwise the reference is moved, which is fine as long as it isn't used again.
+            [] => !local_used_after_expr(cx, local, expr),
             _ => false,
         }
     } else {
@@ -750,30 +1166,680 @@ fn in_postfix_position<'tcx>(cx: &LateContext<'tcx>, e: &'tcx Expr<'tcx>) -> boo
     }
 }
 
-#[derive(Clone, Copy)]
-enum TyCoercionStability {
-    Deref,
-    Reborrow,
-    None,
-}
-impl TyCoercionStability {
-    fn is_deref_stable(self) -> bool {
-        matches!(self, Self::Deref)
+/// Checks if the borrow `e` is `&mut *x` moved into an argument of a generic type. e.g.
+/// `v.push(&mut *x)`
+fn is_moved_generic_reborrow_arg<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    e: &'tcx Expr<'tcx>,
+) -> bool {
+    if let ExprKind::AddrOf(_, _, sub_expr) = e.kind
+        && let Some(use_cx) = expr_use_ctxt(cx, e)
+    {
+        is_moved_generic_reborrow(cx, typeck, e, sub_expr, &use_cx)
+    } else {
+        false
     }
+}
 
-    fn is_reborrow_stable(self) -> bool {
-        matches!(self, Self::Deref | Self::Reborrow)
+/// Checks if `expr` is a borrow which would be inserted by the compiler if it were removed.
+/// Returns the replacement for `expr` along with its applicability. If `allow_parens` is `false`
+/// no replacement is returned when it would need to be wrapped in parentheses.
+///
+/// e.g. `f(&&x)` where `f` takes `&T` and `x: T` can be replaced with `f(&x)`.
+fn needless_borrow_sugg<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+    allow_parens: bool,
+    coercion_fns: &FxHashSet<DefId>,
+    stability_cache: &mut StabilityCache<'tcx>,
+) -> Option<(String, Applicability)> {
+    let typeck = cx.typeck_results();
+    if expr.span.from_expansion() || move_closure_captured_reborrow(cx, expr).is_some() {
+        return None;
     }
+    let Some((RefOp::AddrOf(mutability), sub_expr)) = try_parse_ref_op(cx, typeck, expr) else {
+        return None;
+    };
+    let use_cx = expr_use_ctxt(cx, expr)?;
+    let Some(State::DerefedBorrow(state)) = addr_of_state(
+        cx,
+        typeck,
+        expr,
+        sub_expr,
+        &use_cx,
+        coercion_fns,
+        stability_cache,
+        mutability,
+    ) else {
+        return None;
+    };
 
-    fn for_defined_ty<'tcx>(cx: &LateContext<'tcx>, ty: DefinedTy<'tcx>, for_return: bool) -> Self {
-        match ty {
+    // Skip over any further borrows which can also be removed.
+    let mut kept_expr = sub_expr;
+    for _ in 0..state.count {
+        if kept_expr.span.from_expansion() {
+            break;
+        }
+        match try_parse_ref_op(cx, typeck, kept_expr) {
+            Some((RefOp::AddrOf(_), sub_expr)) => kept_expr = sub_expr,
+            _ => break,
+        }
+    }
+
+    if derefs_through_manually_drop(cx, typeck, expr, kept_expr) {
+        return None;
+    }
+    // Changing which borrow holds a temporary can change how long it lives, and with it when it's
+    // dropped. e.g. `let x: &MutexGuard<_> = &&mutex.lock().unwrap();`
+    if let Some(temp) = borrowed_temporary(kept_expr)
+        && needs_ordered_drop(cx, typeck.expr_ty(temp))
+        && is_let_extended_borrow(cx, expr)
+    {
+        return None;
+    }
+
+    let mut app = Applicability::MachineApplicable;
+    let (snip, snip_is_macro) = snippet_with_context(cx, kept_expr.span, expr.span.ctxt(), "..", &mut app);
+
+    let (precedence, calls_field) = match get_parent_node(cx.tcx, expr.hir_id) {
+        Some(Node::Expr(e)) => match e.kind {
+            ExprKind::Call(callee, _) if callee.hir_id != expr.hir_id => (0, false),
+            ExprKind::MethodCall(_, recv, ..) if recv.hir_id != expr.hir_id => (0, false),
+            ExprKind::Repeat(..) | ExprKind::Tup(_) => (0, false),
+            ExprKind::Call(..) => (PREC_POSTFIX, matches!(kept_expr.kind, ExprKind::Field(..))),
+            _ => (e.precedence().order(), false),
+        },
+        _ => (0, false),
+    };
+    // The kept expression's span includes any parentheses written around it. e.g. `&(&x)`
+    // Only keep them when they're still needed once the outer borrows are gone.
+    let sugg = if snip_is_macro {
+        snip.into()
+    } else if calls_field || kept_expr.precedence().order() < precedence {
+        if has_enclosing_paren(&snip) {
+            snip.into()
+        } else if allow_parens {
+            format!("({snip})")
+        } else {
+            return None;
+        }
+    } else {
+        strip_enclosing_parens(&snip).into()
+    };
+    Some((sugg, app))
+}
+
+fn try_parse_ref_op<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'_>,
+    expr: &'tcx Expr<'_>,
+) -> Option<(RefOp, &'tcx Expr<'tcx>)> {
+    let tcx = cx.tcx;
+    let (is_ufcs, def_id, arg) = match expr.kind {
+        ExprKind::MethodCall(_, arg, [], _) => (false, typeck.type_dependent_def_id(expr.hir_id)?, arg),
+        ExprKind::Call(
+            Expr {
+                kind: ExprKind::Path(path),
+                hir_id,
+                ..
+            },
+            [arg],
+        ) => (true, typeck.qpath_res(path, *hir_id).opt_def_id()?, arg),
+        ExprKind::Unary(UnOp::Deref, sub_expr) if !typeck.expr_ty(sub_expr).is_unsafe_ptr() => {
+            return Some((RefOp::Deref, sub_expr));
+        },
+        ExprKind::AddrOf(BorrowKind::Ref, mutability, sub_expr) => return Some((RefOp::AddrOf(mutability), sub_expr)),
+        _ => return None,
+    };
+    let trait_id = tcx.trait_of_item(def_id);
+    let mutbl = if tcx.is_diagnostic_item(sym::deref_method, def_id)
+        // The method may not be marked as a diagnostic item, fall back to the lang item trait.
+        || (trait_id.is_some() && trait_id == tcx.lang_items().deref_trait())
+    {
+        Mutability::Not
+    } else if trait_id? == tcx.lang_items().deref_mut_trait()? {
+        Mutability::Mut
+    } else {
+        return None;
+    };
+    // `Pin` only forwards to the pointer it wraps, and calling its methods explicitly keeps the pinning
+    // guarantees visible at the call site.
+    if let ty::Adt(adt, _) = *typeck.expr_ty(arg).peel_refs().kind()
+        && Some(adt.did()) == tcx.lang_items().pin_type()
+    {
+        return None;
+    }
+    Some((RefOp::Method { mutbl, is_ufcs }, arg))
+}
+
+/// Checks if both operands of `==` or `!=` dereference nested references of the same type, which
+/// could be compared directly. `PartialEq` for references forwards to the referenced values, so
+/// the result doesn't change. e.g. `**a == **b` or `&**a == &**b` where `a, b: &&str`
+///
+/// Returns the references to compare instead.
+pub(crate) fn peel_comparison_derefs<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &Expr<'_>,
+    lhs: &'tcx Expr<'tcx>,
+    rhs: &'tcx Expr<'tcx>,
+) -> Option<(&'tcx Expr<'tcx>, &'tcx Expr<'tcx>)> {
+    let typeck = cx.typeck_results();
+    let (lhs, rhs) = match (lhs.kind, rhs.kind) {
+        (
+            ExprKind::AddrOf(BorrowKind::Ref, Mutability::Not, lhs),
+            ExprKind::AddrOf(BorrowKind::Ref, Mutability::Not, rhs),
+        ) => (lhs, rhs),
+        (ExprKind::AddrOf(..), _) | (_, ExprKind::AddrOf(..)) => return None,
+        _ => (lhs, rhs),
+    };
+    let peel = |mut e: &'tcx Expr<'tcx>| {
+        let mut deref_count = 0;
+        while let ExprKind::Unary(UnOp::Deref, sub_expr) = e.kind
+            && e.span.eq_ctxt(expr.span)
+            && typeck.expr_ty(sub_expr).is_ref()
+        {
+            e = sub_expr;
+            deref_count += 1;
+        }
+        (e, deref_count)
+    };
+    let ((l, l_count), (r, r_count)) = (peel(lhs), peel(rhs));
+    let ty = typeck.expr_ty(l);
+    // A single deref is the usual way to compare the values of two references.
+    if l_count < 2
+        || l_count != r_count
+        || ty != typeck.expr_ty(r)
+        || is_deref_addrof_base(l)
+        || is_deref_addrof_base(r)
+        || !cx
+            .tcx
+            .lang_items()
+            .eq_trait()
+            .is_some_and(|trait_id| implements_trait(cx, ty, trait_id, &[ty.into()]))
+    {
+        return None;
+    }
+    Some((l, r))
+}
+
+/// Checks if the expression is `&*x` where `x: &T` is captured by the `move` closure the
+/// expression is the result of. e.g. `move || &*x`
+///
+/// Returns the captured expression.
+pub(crate) fn move_closure_captured_reborrow<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &Expr<'tcx>,
+) -> Option<&'tcx Expr<'tcx>> {
+    if let ExprKind::AddrOf(BorrowKind::Ref, Mutability::Not, sub_expr) = expr.kind
+        && let ExprKind::Unary(UnOp::Deref, captured) = sub_expr.kind
+        && let Some(local) = path_to_local(captured)
+        && let ty::Ref(_, _, Mutability::Not) = cx.typeck_results().expr_ty(captured).kind()
+        && !expr.span.from_expansion()
+        && expr.span.eq_ctxt(captured.span)
+    {
+        let mut child_id = expr.hir_id;
+        for (parent_id, parent) in cx.tcx.hir().parent_iter(expr.hir_id) {
+            match parent {
+                Node::Block(block) if block.expr.is_some_and(|e| e.hir_id == child_id) => (),
+                Node::Expr(Expr {
+                    kind: ExprKind::Block(..),
+                    ..
+                }) => (),
+                Node::Expr(Expr {
+                    kind:
+                        ExprKind::Closure(&Closure {
+                            capture_clause: CaptureBy::Value { .. },
+                            def_id,
+                            ..
+                        }),
+                    ..
+                }) if cx
+                    .tcx
+                    .upvars_mentioned(def_id)
+                    .is_some_and(|upvars| upvars.contains_key(&local)) =>
+                {
+                    return Some(captured);
+                },
+                _ => return None,
+            }
+            child_id = parent_id;
+        }
+    }
+    None
+}
+
+// The number of auto-deref steps followed when comparing method and index lookups. A `Deref`
+// impl can return its own type, so the steps don't necessarily end.
+const MAX_DEREF_STEPS: usize = 16;
+
+// Checks if calling the method on a receiver of type `new_ty` would resolve to the same method as
+// on the current receiver of type `recv_ty`. This is the case when none of the types auto-deref
+// passes through before reaching one of the current receiver's auto-deref steps has a method with
+// the same name.
+//
+// e.g. for `(&**x).len()` where `x: &String`, both `&String` and `String` are checked. As `String`
+// has its own `len` method, `x.len()` would call a different method.
+fn is_method_lookup_stable<'tcx>(
+    cx: &LateContext<'tcx>,
+    call: &Expr<'_>,
+    name: Symbol,
+    new_ty: Ty<'tcx>,
+    recv_ty: Ty<'tcx>,
+) -> bool {
+    let recv_steps: Vec<_> = iter::successors(Some(cx.tcx.erase_regions(recv_ty)), |&ty| deref_once(cx, ty))
+        .take(MAX_DEREF_STEPS)
+        .collect();
+    let traits = cx.tcx.in_scope_traits(call.hir_id).unwrap_or_default();
+    for ty in iter::successors(Some(cx.tcx.erase_regions(new_ty)), |&ty| deref_once(cx, ty)).take(MAX_DEREF_STEPS) {
+        if recv_steps.contains(&ty) {
+            return true;
+        }
+        if has_method_named(cx, traits, ty, name) {
+            return false;
+        }
+    }
+    false
+}
+
+// Checks if indexing a value of `new_ty` would use the same `Index` impl as indexing a value of
+// `base_ty`. This is the case when none of the types auto-deref passes through before reaching
+// one of `base_ty`'s auto-deref steps can be indexed.
+//
+// e.g. for `(&**x)[..]` where `x: &String`, both `&String` and `String` are checked. As `String`
+// can be indexed by a range, `x[..]` would use a different impl.
+fn is_index_lookup_stable<'tcx>(cx: &LateContext<'tcx>, new_ty: Ty<'tcx>, base_ty: Ty<'tcx>, idx_ty: Ty<'tcx>) -> bool {
+    let Some(index_id) = cx.tcx.lang_items().index_trait() else {
+        return false;
+    };
+    let idx_ty = cx.tcx.erase_regions(idx_ty);
+    let base_steps: Vec<_> = iter::successors(Some(cx.tcx.erase_regions(base_ty)), |&ty| deref_once(cx, ty))
+        .take(MAX_DEREF_STEPS)
+        .collect();
+    for ty in iter::successors(Some(cx.tcx.erase_regions(new_ty)), |&ty| deref_once(cx, ty)).take(MAX_DEREF_STEPS) {
+        if base_steps.contains(&ty) {
+            return true;
+        }
+        if implements_trait(cx, ty, index_id, &[idx_ty.into()]) {
+            return false;
+        }
+    }
+    false
+}
+
+fn deref_once<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    if let Some(ty) = ty.builtin_deref(true) {
+        Some(ty.ty)
+    } else if let Some(deref_id) = cx.tcx.lang_items().deref_trait()
+        && let Some(target_id) = cx.tcx.lang_items().deref_target()
+        && implements_trait(cx, ty, deref_id, &[])
+    {
+        cx.tcx
+            .try_normalize_erasing_regions(cx.param_env, Ty::new_projection(cx.tcx, target_id, [ty]))
+            .ok()
+    } else {
+        None
+    }
+}
+
+// Checks if a method with the given name could be found on the type, either from an inherent impl,
+// or from a trait in scope implemented for the type or a reference to it.
+fn has_method_named<'tcx>(cx: &LateContext<'tcx>, traits: &[TraitCandidate], ty: Ty<'tcx>, name: Symbol) -> bool {
+    let has_fn_named = |def_id| {
+        cx.tcx
+            .associated_items(def_id)
+            .filter_by_name_unhygienic(name)
+            .any(|item| item.kind == AssocKind::Fn)
+    };
+
+    // Methods can also come from bounds on the type, or the type itself.
+    if matches!(
+        ty.kind(),
+        ty::Param(_) | ty::Alias(..) | ty::Dynamic(..) | ty::Placeholder(_) | ty::Infer(_) | ty::Bound(..)
+    ) {
+        return true;
+    }
+    if let Some(simplified) = simplify_type(cx.tcx, ty, TreatParams::AsCandidateKey)
+        && let inherent_impls = match ty.kind() {
+            ty::Adt(adt, _) => cx.tcx.inherent_impls(adt.did()),
+            _ => &[],
+        }
+        && inherent_impls
+            .iter()
+            .chain(cx.tcx.incoherent_impls(simplified))
+            .any(|&impl_id| has_fn_named(impl_id))
+    {
+        return true;
+    }
+
+    traits.iter().any(|candidate| {
+        has_fn_named(candidate.def_id)
+            && (cx.tcx.generics_of(candidate.def_id).count() > 1
+                || [
+                    ty,
+                    Ty::new_imm_ref(cx.tcx, cx.tcx.lifetimes.re_erased, ty),
+                    Ty::new_mut_ref(cx.tcx, cx.tcx.lifetimes.re_erased, ty),
+                ]
+                .into_iter()
+                .any(|ty| implements_trait(cx, ty, candidate.def_id, &[])))
+    })
+}
+
+// Checks if `sub_expr[idx]` slices the whole of a container to the same type its `Deref` impl
+// resolves to. e.g. `x[..]` where `x: Vec<T>` is the same as `*x`.
+fn is_full_slice_deref(cx: &LateContext<'_>, expr: &Expr<'_>, sub_expr: &Expr<'_>, idx: &Expr<'_>) -> bool {
+    let typeck = cx.typeck_results();
+    let sub_ty = typeck.expr_ty(sub_expr);
+    // Indexing auto-derefs references, so `x[..]` would be `**x` in that case.
+    if sub_ty.is_ref() || !is_type_lang_item(cx, typeck.expr_ty(idx), LangItem::RangeFull) {
+        return false;
+    }
+    let Some(target_id) = cx.tcx.lang_items().deref_target() else {
+        return false;
+    };
+    // Custom `Index` impls may return something other than the `Deref` target.
+    cx.tcx
+        .try_normalize_erasing_regions(
+            cx.param_env,
+            Ty::new_projection(cx.tcx, target_id, [cx.tcx.erase_regions(sub_ty)]),
+        )
+        .is_ok_and(|target| target == cx.tcx.erase_regions(typeck.expr_ty(expr)))
+}
+
+// Checks if the adjustments contains a deref of `ManuallyDrop<_>`
+fn adjust_derefs_manually_drop<'tcx>(adjustments: &'tcx [Adjustment<'tcx>], mut ty: Ty<'tcx>) -> bool {
+    adjustments.iter().any(|a| {
+        let ty = mem::replace(&mut ty, a.target);
+        matches!(a.kind, Adjust::Deref(Some(ref op)) if op.mutbl == Mutability::Mut) && is_manually_drop(ty)
+    })
+}
+
+// Checks if any expression from `inner` up to `outer` is a `ManuallyDrop<_>`, or if the
+// adjustments applied to `outer` deref through one. These usually show up in `unsafe` code managing
+// drops by hand, so the borrows and derefs around them are left as written.
+fn derefs_through_manually_drop<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    outer: &Expr<'_>,
+    inner: &Expr<'_>,
+) -> bool {
+    let mut ty = typeck.expr_ty(outer);
+    if typeck.expr_adjustments(outer).iter().any(|a| {
+        let ty = mem::replace(&mut ty, a.target);
+        matches!(a.kind, Adjust::Deref(_)) && is_manually_drop(ty)
+    }) {
+        return true;
+    }
+    let mut e = inner;
+    loop {
+        if is_manually_drop(typeck.expr_ty(e)) {
+            return true;
+        }
+        if e.hir_id == outer.hir_id {
+            return false;
+        }
+        match get_parent_expr(cx, e) {
+            Some(parent) => e = parent,
+            None => return false,
+        }
+    }
+}
+
+/// Checks if a closure containing the expression captures the local by value.
+fn is_moved_into_closure(cx: &LateContext<'_>, e: &Expr<'_>, local: HirId) -> bool {
+    cx.tcx.hir().parent_iter(e.hir_id).any(|(_, node)| {
+        if let Node::Expr(Expr {
+            kind: ExprKind::Closure(closure),
+            ..
+        }) = node
+        {
+            cx.typeck_results()
+                .closure_min_captures_flattened(closure.def_id)
+                .any(|capture| {
+                    matches!(capture.place.base, PlaceBase::Upvar(var) if var.var_path.hir_id == local)
+                        && matches!(capture.info.capture_kind, UpvarCapture::ByValue)
+                })
+        } else {
+            false
+        }
+    })
+}
+
+// Counts the reference operations in the chain starting at the expression. e.g. `&**x` has three
+fn ref_op_chain_len<'tcx>(cx: &LateContext<'tcx>, typeck: &'tcx TypeckResults<'tcx>, mut e: &'tcx Expr<'tcx>) -> u64 {
+    let mut len = 0;
+    while let Some((_, sub_expr)) = try_parse_ref_op(cx, typeck, e) {
+        len += 1;
+        e = sub_expr;
+    }
+    len
+}
+
+// Checks if the chain of reference operations starting at the expression contains a `deref` method
+// call. e.g. `&*x.deref()`
+fn ref_op_chain_has_method<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    mut e: &'tcx Expr<'tcx>,
+) -> bool {
+    while let Some((kind, sub_expr)) = try_parse_ref_op(cx, typeck, e) {
+        if let RefOp::Method { .. } = kind {
+            return true;
+        }
+        e = sub_expr;
+    }
+    false
+}
+
+/// Checks if a borrow of the expression is followed by an explicit deref in the chain of reference
+/// operations. e.g. the `&**x` in `&&**x`, or the `&*&x` in `&&*&x`
+fn borrow_continues_to_explicit_deref<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    e: &'tcx Expr<'tcx>,
+) -> bool {
+    match try_parse_ref_op(cx, typeck, e) {
+        Some((RefOp::Deref, sub_expr)) => {
+            !typeck.expr_ty(sub_expr).is_ref()
+                || matches!(
+                    try_parse_ref_op(cx, typeck, sub_expr),
+                    Some((RefOp::Deref | RefOp::AddrOf(_), _))
+                )
+        },
+        _ => false,
+    }
+}
+
+/// Checks if the chain of reference operations starting at the expression contains a borrow which
+/// is immediately dereferenced, and is linted by `deref_addrof`. e.g. `&*&x`
+fn ref_op_chain_has_deref_addrof<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    mut e: &'tcx Expr<'tcx>,
+) -> bool {
+    while let Some((kind, sub_expr)) = try_parse_ref_op(cx, typeck, e) {
+        if let RefOp::Deref = kind
+            && let ExprKind::AddrOf(BorrowKind::Ref, _, target) = sub_expr.kind
+            && sub_expr.span.eq_ctxt(e.span)
+            && !target.span.from_expansion()
+            && !is_lint_allowed(cx, DEREF_ADDROF, e.hir_id)
+        {
+            return true;
+        }
+        e = sub_expr;
+    }
+    false
+}
+
+/// Checks if the base of a chain of derefs is a borrow. Derefs of a borrow are handled by
+/// `deref_addrof`. e.g. `**&&x`
+fn is_deref_addrof_base(base: &Expr<'_>) -> bool {
+    matches!(base.kind, ExprKind::AddrOf(..))
+}
+
+/// Checks if the chain of reference operations dereferences either a type with interior
+/// mutability or a guard borrowing one. e.g. `&*cell.borrow()` or `&**mutex.lock().unwrap()`
+fn derefs_interior_mut<'tcx>(cx: &LateContext<'tcx>, typeck: &TypeckResults<'tcx>, mut e: &Expr<'_>) -> bool {
+    loop {
+        match e.kind {
+            ExprKind::AddrOf(BorrowKind::Ref, _, sub_expr) => e = sub_expr,
+            ExprKind::Unary(UnOp::Deref, sub_expr) => {
+                let (ptr_ty, target_ty) = (typeck.expr_ty(sub_expr), typeck.expr_ty(e));
+                if matches!(
+                    get_type_diagnostic_name(cx, ptr_ty),
+                    Some(
+                        sym::RefCellRef
+                            | sym::RefCellRefMut
+                            | sym::MutexGuard
+                            | sym::RwLockReadGuard
+                            | sym::RwLockWriteGuard
+                    )
+                ) || (!target_ty.is_ref() && is_interior_mut_ty(cx, target_ty))
+                {
+                    return true;
+                }
+                e = sub_expr;
+            },
+            _ => return false,
+        }
+    }
+}
+
+// Checks if the borrow is in an extending position of a `let` initializer. Temporaries borrowed in
+// these positions live until the end of the enclosing block rather than the end of the statement.
+//
+// e.g. both `let x = &temp();` and `let x = (&temp(), 0);`
+fn is_let_extended_borrow(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    let mut child_id = expr.hir_id;
+    for (parent_id, parent) in cx.tcx.hir().parent_iter(expr.hir_id) {
+        match parent {
+            Node::Local(local) => return local.init.is_some_and(|init| init.hir_id == child_id),
+            Node::Block(block) if block.expr.is_some_and(|e| e.hir_id == child_id) => (),
+            Node::ExprField(_) => (),
+            Node::Expr(e) => match e.kind {
+                ExprKind::AddrOf(..)
+                | ExprKind::Array(_)
+                | ExprKind::Block(..)
+                | ExprKind::Cast(..)
+                | ExprKind::Tup(_)
+                | ExprKind::Struct(_, _, None) => (),
+                _ => return false,
+            },
+            _ => return false,
+        }
+        child_id = parent_id;
+    }
+    false
+}
+
+// Finds the temporary a borrow is taken of, skipping over any further borrows and derefs.
+// e.g. `m.lock().unwrap()` for `&*m.lock().unwrap()`
+fn borrowed_temporary<'tcx>(mut e: &'tcx Expr<'tcx>) -> Option<&'tcx Expr<'tcx>> {
+    while let ExprKind::AddrOf(_, _, inner) | ExprKind::Unary(UnOp::Deref, inner) = e.kind {
+        e = inner;
+    }
+    (!e.is_syntactic_place_expr()).then_some(e)
+}
+
+/// Checks whether the body was generated by a derive macro. A proc macro can give its output the
+/// spans of its input, in which case the body's span won't be marked as coming from an expansion.
+/// The expansion which defined the body's owner is still recorded.
+fn is_derive_body(cx: &LateContext<'_>, body: &Body<'_>) -> bool {
+    let is_derive = |kind: ExpnKind| matches!(kind, ExpnKind::Macro(MacroKind::Derive, _));
+    let owner = cx.tcx.hir().body_owner_def_id(body.id());
+    body.value.span.source_callee().is_some_and(|data| is_derive(data.kind))
+        || iter::successors(Some(cx.tcx.expn_that_defined(owner)), |&expn| {
+            (expn != ExpnId::root()).then(|| expn.expn_data().parent)
+        })
+        .any(|expn| is_derive(expn.expn_data().kind))
+}
+
+// Checks if match ergonomics will peel every reference off of the value matched by the pattern.
+fn pat_peels_refs(cx: &LateContext<'_>, pat: &Pat<'_>) -> bool {
+    match pat.kind {
+        PatKind::Struct(..) | PatKind::TupleStruct(..) | PatKind::Tuple(..) | PatKind::Slice(..) => true,
+        PatKind::Path(ref qpath) => matches!(
+            cx.qpath_res(qpath, pat.hir_id),
+            Res::Def(DefKind::Ctor(..), _) | Res::SelfCtor(_)
+        ),
+        PatKind::Or(pats) => pats.iter().all(|pat| pat_peels_refs(cx, pat)),
+        _ => false,
+    }
+}
+
+// Checks whether the type for a deref call actually changed the type, not just the mutability of
+// the reference.
+fn deref_method_same_type<'tcx>(result_ty: Ty<'tcx>, arg_ty: Ty<'tcx>) -> bool {
+    match (result_ty.kind(), arg_ty.kind()) {
+        (ty::Ref(_, result_ty, _), ty::Ref(_, arg_ty, _)) => result_ty == arg_ty,
+
+        // The result type for a deref method is always a reference
+        // Not matching the previous pattern means the argument type is not a reference
+        // This means that the type did change
+        _ => false,
+    }
+}
+
+fn in_postfix_position<'tcx>(cx: &LateContext<'tcx>, e: &'tcx Expr<'tcx>) -> bool {
+    if let Some(parent) = get_parent_expr(cx, e)
+        && parent.span.eq_ctxt(e.span)
+    {
+        match parent.kind {
+            ExprKind::Call(child, _) | ExprKind::MethodCall(_, child, _, _) | ExprKind::Index(child, _, _)
+                if child.hir_id == e.hir_id =>
+            {
+                true
+            },
+            ExprKind::Match(.., MatchSource::TryDesugar(_) | MatchSource::AwaitDesugar) | ExprKind::Field(_, _) => true,
+            _ => false,
+        }
+    } else {
+        false
+    }
+}
+
+/// Maps a signature type, along with its `ParamEnv` and whether it's the return type, to its
+/// auto-deref stability.
+type StabilityCache<'tcx> = FxHashMap<(ParamEnv<'tcx>, Ty<'tcx>, bool), TyCoercionStability>;
+
+#[derive(Clone, Copy)]
+enum TyCoercionStability {
+    Deref,
+    /// Auto-deref is stable, but the target type depends on generic parameters.
+    DerefGeneric,
+    Reborrow,
+    None,
+}
+impl TyCoercionStability {
+    fn is_deref_stable(self) -> bool {
+        matches!(self, Self::Deref | Self::DerefGeneric)
+    }
+
+    fn is_reborrow_stable(self) -> bool {
+        matches!(self, Self::Deref | Self::DerefGeneric | Self::Reborrow)
+    }
+
+    fn for_use_cx<'tcx>(cx: &LateContext<'tcx>, use_cx: &ExprUseCtxt<'tcx>, cache: &mut StabilityCache<'tcx>) -> Self {
+        use_cx.node.defined_ty(cx).map_or(Self::None, |ty| {
+            Self::for_defined_ty(cx, ty, use_cx.node.is_return(), cache)
+        })
+    }
+
+    fn for_defined_ty<'tcx>(
+        cx: &LateContext<'tcx>,
+        ty: DefinedTy<'tcx>,
+        for_return: bool,
+        cache: &mut StabilityCache<'tcx>,
+    ) -> Self {
+        match ty {
             DefinedTy::Hir(ty) => Self::for_hir_ty(ty),
-            DefinedTy::Mir(ty) => Self::for_mir_ty(
-                cx.tcx,
-                ty.param_env,
-                cx.tcx.instantiate_bound_regions_with_erased(ty.value),
-                for_return,
-            ),
+            DefinedTy::Mir(ty) => {
+                let param_env = ty.param_env;
+                let ty = cx.tcx.instantiate_bound_regions_with_erased(ty.value);
+                *cache
+                    .entry((param_env, ty, for_return))
+                    .or_insert_with(|| Self::for_mir_ty(cx.tcx, param_env, ty, for_return))
+            },
         }
     }
 
@@ -815,10 +1881,14 @@ fn for_hir_ty<'tcx>(ty: &'tcx hir::Ty<'tcx>) -> Self {
                         })
                     {
                         Self::Reborrow
+                    } else if ty_contains_param(ty.ty) {
+                        Self::DerefGeneric
                     } else {
                         Self::Deref
                     }
                 },
+                // Slices and arrays don't implement `Deref`, so at most one type in the deref chain
+                // can match, whatever the element type is. e.g. `&[&_]`
                 TyKind::Slice(_)
                 | TyKind::Array(..)
                 | TyKind::Ptr(_)
@@ -842,13 +1912,17 @@ fn for_mir_ty<'tcx>(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, ty: Ty<'tcx>,
         };
 
         ty = tcx.try_normalize_erasing_regions(param_env, ty).unwrap_or(ty);
+        // Whether the target is a reference to a reference. Auto-deref will always stop at the
+        // innermost reference in this case, no matter what the generic parameter resolves to.
+        let mut is_nested_ref = false;
         loop {
             break match *ty.kind() {
                 ty::Ref(_, ref_ty, _) => {
                     ty = ref_ty;
+                    is_nested_ref = true;
                     continue;
                 },
-                ty::Param(_) if for_return => Self::Deref,
+                ty::Param(_) if for_return || is_nested_ref => Self::DerefGeneric,
                 ty::Alias(ty::Weak | ty::Inherent, _) => unreachable!("should have been normalized away above"),
                 ty::Alias(ty::Projection, _) if !for_return && ty.has_non_region_param() => Self::Reborrow,
                 ty::Infer(_)
@@ -865,6 +1939,7 @@ fn for_mir_ty<'tcx>(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, ty: Ty<'tcx>,
                 {
                     Self::Reborrow
                 },
+                ty::Adt(..) | ty::Alias(ty::Projection, _) if ty.has_non_region_param() => Self::DerefGeneric,
                 ty::Bool
                 | ty::Char
                 | ty::Int(_)
@@ -920,6 +1995,35 @@ fn visit_generic_arg(&mut self, arg: &hir::GenericArg<'_>) {
     v.0
 }
 
+// Checks whether a type refers to a generic parameter at some point.
+// e.g. `T`, `Vec<T>`, `T::Target`
+fn ty_contains_param(ty: &hir::Ty<'_>) -> bool {
+    struct V(bool);
+    impl Visitor<'_> for V {
+        fn visit_ty(&mut self, ty: &hir::Ty<'_>) {
+            if self.0
+                || matches!(
+                    ty.kind,
+                    TyKind::Path(QPath::Resolved(
+                        _,
+                        Path {
+                            res: Res::Def(DefKind::TyParam, _) | Res::SelfTyParam { .. },
+                            ..
+                        },
+                    ))
+                )
+            {
+                self.0 = true;
+            } else {
+                walk_ty(self, ty);
+            }
+        }
+    }
+    let mut v = V(false);
+    v.visit_ty(ty);
+    v.0
+}
+
 fn ty_contains_field(ty: Ty<'_>, name: Symbol) -> bool {
     if let ty::Adt(adt, _) = *ty.kind() {
         adt.is_struct() && adt.all_fields().any(|f| f.name == name)
@@ -935,7 +2039,28 @@ fn report<'tcx>(
     state: State,
     data: StateData<'tcx>,
     typeck: &'tcx TypeckResults<'tcx>,
+    conf: &mut Dereferencing<'tcx>,
 ) {
+    if matches!(
+        state,
+        State::DerefedBorrow(_) | State::ExplicitDeref { .. } | State::ExplicitDerefField { .. }
+    ) && derefs_interior_mut(cx, typeck, data.first_expr)
+    {
+        // Keep the operators which access the cell's contents explicit.
+        return;
+    }
+    if !matches!(state, State::DerefMethod { .. } | State::Borrow { .. })
+        && ref_op_chain_has_deref_addrof(cx, typeck, data.first_expr)
+    {
+        // `deref_addrof` already removes the borrow along with the deref. e.g. `&*&x` becomes `&x`
+        return;
+    }
+
+    let chain = ChainSugg::new(cx, typeck, data.first_expr, conf);
+    let suggest = |diag: &mut Diagnostic, span: Span, msg: &'static str, sugg: String, app: Applicability| {
+        chain.suggest(diag, span, msg, sugg, app);
+    };
+
     match state {
         State::DerefMethod {
             ty_changed_count,
@@ -943,8 +2068,23 @@ fn report<'tcx>(
             mutbl,
         } => {
             let mut app = Applicability::MachineApplicable;
-            let (expr_str, _expr_is_macro_call) =
+            let (snip, expr_is_macro_call) =
                 snippet_with_context(cx, expr.span, data.first_expr.span.ctxt(), "..", &mut app);
+            // The receiver's span includes any parentheses written around it. e.g. `(x).deref()`
+            // Drop them when the operators can be applied without them, and only add them when
+            // they aren't already there.
+            let parenthesize = |prec: i8| {
+                if expr_is_macro_call {
+                    snip.clone()
+                } else if expr.precedence().order() >= prec {
+                    Cow::Owned(strip_enclosing_parens(&snip).into())
+                } else if has_enclosing_paren(&snip) {
+                    snip.clone()
+                } else {
+                    Cow::Owned(format!("({snip})"))
+                }
+            };
+            let expr_str = parenthesize(PREC_PREFIX);
             let ty = typeck.expr_ty(expr);
             let (_, ref_count) = peel_mid_ty_refs(ty);
             let deref_str = if ty_changed_count >= ref_count && ref_count != 0 {
@@ -967,38 +2107,74 @@ fn report<'tcx>(
                 "&"
             };
 
-            // expr_str (the suggestion) is never shown if is_final_ufcs is true, since it's
-            // `expr.kind == ExprKind::Call`. Therefore, this is, afaik, always unnecessary.
-            /*
-            expr_str = if !expr_is_macro_call && is_final_ufcs && expr.precedence().order() < PREC_PREFIX {
-                Cow::Owned(format!("({expr_str})"))
-            } else {
-                expr_str
-            };
-            */
-
             // Fix #10850, do not lint if it's `Foo::deref` instead of `foo.deref()`.
             if is_ufcs {
                 return;
             }
 
-            span_lint_and_sugg(
+            // The target of a compound assignment. e.g. `*x.deref_mut() += 1`
+            // The added borrow would be immediately dereferenced, so replace the whole place
+            // expression instead.
+            let (span, sugg) = if is_deref_coerced_let_init(cx, typeck, expr, data.first_expr, mutbl) {
+                // The receiver can be coerced to the declared type. e.g. `let s: &str = x.deref();`
+                let expr_str = if expr_is_macro_call {
+                    expr_str
+                } else {
+                    Cow::Owned(strip_enclosing_parens(&expr_str).into())
+                };
+                (data.first_expr.span, expr_str.into_owned())
+            } else if ty_changed_count == 1
+                && let Some(method) = slice_method_for_deref(
+                    cx,
+                    typeck,
+                    expr,
+                    data.first_expr,
+                    mutbl,
+                    &conf.msrv,
+                    &mut conf.stability_cache,
+                )
+            {
+                // `&*x` can't be shortened to `&x` here, so borrow the slice by name instead.
+                (
+                    data.first_expr.span,
+                    format!("{}.{method}()", parenthesize(PREC_POSTFIX)),
+                )
+            } else if addr_of_str == "&mut "
+                && let Some(parent) = get_parent_expr(cx, data.first_expr)
+                && let ExprKind::Unary(UnOp::Deref, _) = parent.kind
+                && parent.span.eq_ctxt(data.first_expr.span)
+                && let Some(assign) = get_parent_expr(cx, parent)
+                && let ExprKind::AssignOp(_, lhs, _) = assign.kind
+                && lhs.hir_id == parent.hir_id
+            {
+                (parent.span, format!("{deref_str}{expr_str}"))
+            } else {
+                (data.first_expr.span, format!("{addr_of_str}{deref_str}{expr_str}"))
+            };
+
+            span_lint_and_then(
                 cx,
                 EXPLICIT_DEREF_METHODS,
-                data.first_expr.span,
+                span,
                 match mutbl {
                     Mutability::Not => "explicit `deref` method call",
                     Mutability::Mut => "explicit `deref_mut` method call",
                 },
-                "try",
-                format!("{addr_of_str}{deref_str}{expr_str}"),
-                app,
+                |diag| {
+                    suggest(diag, span, "try", sugg, app);
+                },
             );
         },
         State::DerefedBorrow(state) => {
-            let mut app = Applicability::MachineApplicable;
-            let (snip, snip_is_macro) =
-                snippet_with_context(cx, expr.span, data.first_expr.span.ctxt(), "..", &mut app);
+            let Some((sugg, app)) = needless_borrow_sugg(
+                cx,
+                data.first_expr,
+                conf.needless_borrow_allow_parens,
+                &conf.coercion_fn_def_ids,
+                &mut conf.stability_cache,
+            ) else {
+                return;
+            };
             span_lint_hir_and_then(
                 cx,
                 NEEDLESS_BORROW,
@@ -1006,38 +2182,18 @@ fn report<'tcx>(
                 data.first_expr.span,
                 state.msg,
                 |diag| {
-                    let (precedence, calls_field) = match get_parent_node(cx.tcx, data.first_expr.hir_id) {
-                        Some(Node::Expr(e)) => match e.kind {
-                            ExprKind::Call(callee, _) if callee.hir_id != data.first_expr.hir_id => (0, false),
-                            ExprKind::Call(..) => (PREC_POSTFIX, matches!(expr.kind, ExprKind::Field(..))),
-                            _ => (e.precedence().order(), false),
-                        },
-                        _ => (0, false),
-                    };
-                    let sugg = if !snip_is_macro
-                        && (calls_field || expr.precedence().order() < precedence)
-                        && !has_enclosing_paren(&snip)
-                    {
-                        format!("({snip})")
-                    } else {
-                        snip.into()
-                    };
-                    diag.span_suggestion(data.first_expr.span, "change this to", sugg, app);
+                    suggest(diag, data.first_expr.span, "change this to", sugg, app);
+                    if state.keeps_mut_reborrow {
+                        diag.note(
+                            "the remaining mutable reference can't be removed, as the compiler won't reborrow it \
+                            here and it would be moved instead",
+                        );
+                    }
                 },
             );
         },
-        State::ExplicitDeref { mutability } => {
-            if matches!(
-                expr.kind,
-                ExprKind::Block(..)
-                    | ExprKind::ConstBlock(_)
-                    | ExprKind::If(..)
-                    | ExprKind::Loop(..)
-                    | ExprKind::Match(..)
-            ) && let ty::Ref(_, ty, _) = data.adjusted_ty.kind()
-                && ty.is_sized(cx.tcx, cx.param_env)
-            {
-                // Rustc bug: auto deref doesn't work on block expression when targeting sized types.
+        State::ExplicitDeref { mutability, stability } => {
+            if is_explicit_deref_required(cx, typeck, expr, &data, mutability) {
                 return;
             }
 
@@ -1059,7 +2215,15 @@ fn report<'tcx>(
                 data.first_expr.span,
                 "deref which would be done by auto-deref",
                 |diag| {
-                    let mut app = Applicability::MachineApplicable;
+                    // Inference in generic code can still resolve differently once the deref is removed.
+                    let (mut app, reason) = if matches!(stability, TyCoercionStability::DerefGeneric) {
+                        (
+                            Applicability::MaybeIncorrect,
+                            Some(MaybeIncorrectReason::InferredTarget),
+                        )
+                    } else {
+                        (Applicability::MachineApplicable, None)
+                    };
                     let (snip, snip_is_macro) =
                         snippet_with_context(cx, expr.span, data.first_expr.span.ctxt(), "..", &mut app);
                     let sugg =
@@ -1068,33 +2232,15 @@ fn report<'tcx>(
                         } else {
                             format!("{prefix}{snip}")
                         };
-                    diag.span_suggestion(data.first_expr.span, "try", sugg, app);
+                    suggest(diag, data.first_expr.span, "try", sugg, app);
+                    help_maybe_incorrect(diag, app, reason);
                 },
             );
         },
-        State::ExplicitDerefField {
-            derefs_manually_drop, ..
-        } => {
-            let (snip_span, needs_parens) = if matches!(expr.kind, ExprKind::Field(..))
-                && (derefs_manually_drop
-                    || adjust_derefs_manually_drop(
-                        typeck.expr_adjustments(data.first_expr),
-                        typeck.expr_ty(data.first_expr),
-                    )) {
-                // `DerefMut` will not be automatically applied to `ManuallyDrop<_>`
-                // field expressions when the base type is a union and the parent
-                // expression is also a field access.
-                //
-                // e.g. `&mut x.y.z` where `x` is a union, and accessing `z` requires a
-                // deref through `ManuallyDrop<_>` will not compile.
-                let parent_id = cx.tcx.hir().parent_id(expr.hir_id);
-                if parent_id == data.first_expr.hir_id {
-                    return;
-                }
-                (cx.tcx.hir_node(parent_id).expect_expr().span, true)
-            } else {
-                (expr.span, false)
-            };
+        State::ExplicitDerefField { .. } => {
+            if derefs_through_manually_drop(cx, typeck, data.first_expr, expr) {
+                return;
+            }
             span_lint_hir_and_then(
                 cx,
                 EXPLICIT_AUTO_DEREF,
@@ -1103,20 +2249,396 @@ fn report<'tcx>(
                 "deref which would be done by auto-deref",
                 |diag| {
                     let mut app = Applicability::MachineApplicable;
-                    let snip = snippet_with_context(cx, snip_span, data.first_expr.span.ctxt(), "..", &mut app).0;
-                    let sugg = if needs_parens {
-                        format!("({snip})")
-                    } else {
-                        snip.into_owned()
-                    };
-                    diag.span_suggestion(data.first_expr.span, "try", sugg, app);
+                    let snip = snippet_with_context(cx, expr.span, data.first_expr.span.ctxt(), "..", &mut app).0;
+                    suggest(diag, data.first_expr.span, "try", snip.into_owned(), app);
                 },
             );
         },
-        State::Borrow { .. } | State::Reborrow { .. } => (),
+        State::Reborrow { mutability, .. } => {
+            if let ty::Ref(_, pointee, ref_mutability) = *typeck.expr_ty(expr).kind()
+                && ref_mutability == mutability
+                && match mutability {
+                    Mutability::Not => {
+                        matches!(pointee.kind(), ty::Param(_))
+                            && !is_linted_by_borrow_deref_ref(cx, typeck, data.first_expr)
+                    },
+                    Mutability::Mut => {
+                        matches!(pointee.kind(), ty::Param(_))
+                            || is_moved_generic_reborrow_arg(cx, typeck, data.first_expr)
+                    },
+                }
+            {
+                span_lint_hir_and_then(
+                    cx,
+                    REDUNDANT_REBORROW,
+                    data.first_expr.hir_id,
+                    data.first_expr.span,
+                    "this reborrow results in the same type as the original reference",
+                    |diag| {
+                        let mut app = Applicability::MachineApplicable;
+                        let snip = snippet_with_context(cx, expr.span, data.first_expr.span.ctxt(), "..", &mut app).0;
+                        suggest(diag, data.first_expr.span, "try", snip.into_owned(), app);
+                    },
+                );
+            }
+        },
+        State::Borrow { .. } => (),
+    }
+}
+
+/// Checks if the derefs between `data.first_expr` and `expr` can't be left to auto-deref even
+/// though the target type allows it.
+fn is_explicit_deref_required<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    expr: &'tcx Expr<'_>,
+    data: &StateData<'tcx>,
+    mutability: Option<Mutability>,
+) -> bool {
+    if matches!(
+        expr.kind,
+        ExprKind::Block(..) | ExprKind::ConstBlock(_) | ExprKind::If(..) | ExprKind::Loop(..) | ExprKind::Match(..)
+    ) && let ty::Ref(_, ty, _) = data.adjusted_ty.kind()
+        && ty.is_sized(cx.tcx, cx.param_env)
+    {
+        // Rustc bug: auto deref doesn't work on block expression when targeting sized types.
+        return true;
+    }
+    if let Some(parent) = get_parent_expr(cx, data.first_expr)
+        && let ExprKind::MethodCall(name, recv, ..) = parent.kind
+        && recv.hir_id == data.first_expr.hir_id
+        && !is_method_lookup_stable(cx, parent, name.ident.name, typeck.expr_ty(expr), typeck.expr_ty(recv))
+    {
+        return true;
+    }
+    if derefs_through_manually_drop(cx, typeck, data.first_expr, expr) {
+        return true;
+    }
+    // Borrowing the temporary directly would extend its lifetime to the end of the block, changing
+    // when it's dropped. e.g. `let x: &str = &*lock.read().unwrap();`
+    mutability.is_some()
+        && !expr.is_syntactic_place_expr()
+        && needs_ordered_drop(cx, typeck.expr_ty(expr))
+        && is_let_extended_borrow(cx, data.first_expr)
+}
+
+// Checks the arguments of `write!` and `writeln!` for explicit derefs through references and boxes.
+// e.g. `write!(f, "{}", &*self.x)` where `self.x: Box<T>`
+//
+// Only arguments which are exclusively formatted with `Display` or `Debug` are checked. Both
+// traits are implemented for `&T` and `Box<T>` by forwarding to `T`.
+fn check_write_macro_args<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>, conf: &Dereferencing<'_>) {
+    let Some(macro_call) = root_macro_call_first_node(cx, expr) else {
+        return;
+    };
+    if !matches!(
+        cx.tcx.get_diagnostic_name(macro_call.def_id),
+        Some(sym::write_macro | sym::writeln_macro)
+    ) {
+        return;
+    }
+    let Some(format_args) = find_format_args(cx, expr, macro_call.expn) else {
+        return;
+    };
+    let typeck = cx.typeck_results();
+
+    for (index, arg) in format_args.arguments.all_args().iter().enumerate() {
+        // Implicitly captured arguments are always a plain identifier.
+        if matches!(arg.kind, FormatArgumentKind::Captured(_))
+            || arg.expr.span.from_expansion()
+            || !format_args.template.iter().all(|piece| match piece {
+                FormatArgsPiece::Placeholder(placeholder) => {
+                    (placeholder.argument.index != Ok(index)
+                        || matches!(placeholder.format_trait, FormatTrait::Display | FormatTrait::Debug))
+                        && [&placeholder.format_options.width, &placeholder.format_options.precision]
+                            .into_iter()
+                            .all(|count| !matches!(count, Some(FormatCount::Argument(pos)) if pos.index == Ok(index)))
+                },
+                FormatArgsPiece::Literal(_) => true,
+            })
+        {
+            continue;
+        }
+        let Ok(arg_expr) = find_format_arg_expr(expr, arg) else {
+            continue;
+        };
+
+        let mut base = match arg_expr.kind {
+            ExprKind::AddrOf(BorrowKind::Ref, Mutability::Not, e) => e,
+            _ => arg_expr,
+        };
+        let mut deref_count = 0;
+        while let ExprKind::Unary(UnOp::Deref, e) = base.kind
+            && let ty = typeck.expr_ty(e)
+            && (ty.is_ref() || ty.is_box())
+        {
+            base = e;
+            deref_count += 1;
+        }
+        if deref_count == 0 || is_deref_addrof_base(base) || !base.span.eq_ctxt(arg_expr.span) {
+            continue;
+        }
+
+        span_lint_hir_and_then(
+            cx,
+            EXPLICIT_AUTO_DEREF,
+            arg_expr.hir_id,
+            arg_expr.span,
+            "deref which would be done by auto-deref",
+            |diag| {
+                let mut app = Applicability::MachineApplicable;
+                let snip = snippet_with_context(cx, base.span, arg_expr.span.ctxt(), "..", &mut app).0;
+                ChainSugg::new(cx, typeck, arg_expr, conf).suggest(diag, arg_expr.span, "try", snip.into_owned(), app);
+            },
+        );
+    }
+}
+
+// Checks for `assert_eq!(&a, &b)`. The macro already borrows both operands, so the borrows can be
+// removed without moving either value.
+fn check_assert_eq_macro_args<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>, conf: &Dereferencing<'_>) {
+    let Some(macro_call) = root_macro_call_first_node(cx, expr) else {
+        return;
+    };
+    if !matches!(
+        cx.tcx.get_diagnostic_name(macro_call.def_id),
+        Some(sym::assert_eq_macro | sym::assert_ne_macro | sym::debug_assert_eq_macro | sym::debug_assert_ne_macro)
+    ) {
+        return;
+    }
+    let Some((lhs, rhs, _)) = find_assert_eq_args(cx, expr, macro_call.expn) else {
+        return;
+    };
+    let typeck = cx.typeck_results();
+
+    if let ExprKind::AddrOf(BorrowKind::Ref, Mutability::Not, lhs_inner) = lhs.kind
+        && let ExprKind::AddrOf(BorrowKind::Ref, Mutability::Not, rhs_inner) = rhs.kind
+        && !lhs.span.from_expansion()
+        && !rhs.span.from_expansion()
+        && lhs_inner.span.eq_ctxt(lhs.span)
+        && rhs_inner.span.eq_ctxt(rhs.span)
+        && let Some(eq_trait) = cx.tcx.lang_items().eq_trait()
+        // `&A: PartialEq<&B>` is implemented through `A: PartialEq<B>`, but check in case either
+        // type was only inferred through the borrow.
+        && implements_trait(
+            cx,
+            typeck.expr_ty(lhs_inner),
+            eq_trait,
+            &[typeck.expr_ty(rhs_inner).into()],
+        )
+    {
+        for (arg, inner) in [(lhs, lhs_inner), (rhs, rhs_inner)] {
+            span_lint_hir_and_then(
+                cx,
+                NEEDLESS_BORROW,
+                arg.hir_id,
+                arg.span,
+                "this expression borrows a value the compiler would automatically borrow",
+                |diag| {
+                    let mut app = Applicability::MachineApplicable;
+                    let snip = snippet_with_context(cx, inner.span, arg.span.ctxt(), "..", &mut app).0;
+                    ChainSugg::new(cx, typeck, arg, conf).suggest(
+                        diag,
+                        arg.span,
+                        "change this to",
+                        snip.into_owned(),
+                        app,
+                    );
+                },
+            );
+        }
+    }
+}
+
+/// Whether a replacement can be suggested for a chain of reference operations.
+#[derive(Clone, Copy)]
+struct ChainSugg {
+    too_long: bool,
+    machine_applicable_only: bool,
+}
+impl ChainSugg {
+    fn new<'tcx>(
+        cx: &LateContext<'tcx>,
+        typeck: &'tcx TypeckResults<'tcx>,
+        first_expr: &'tcx Expr<'tcx>,
+        conf: &Dereferencing<'_>,
+    ) -> Self {
+        Self {
+            // Long chains are usually generated code. Building a replacement for them isn't worth it.
+            too_long: ref_op_chain_len(cx, typeck, first_expr) > conf.max_suggested_chain_length,
+            machine_applicable_only: conf.machine_applicable_only,
+        }
+    }
+
+    fn suggest(self, diag: &mut Diagnostic, span: Span, msg: &'static str, sugg: String, app: Applicability) {
+        if self.can_suggest(diag, app) {
+            diag.span_suggestion(span, msg, sugg, app);
+        }
+    }
+
+    fn multipart_suggest(
+        self,
+        diag: &mut Diagnostic,
+        msg: &'static str,
+        sugg: Vec<(Span, String)>,
+        app: Applicability,
+    ) {
+        if self.can_suggest(diag, app) {
+            diag.multipart_suggestion(msg, sugg, app);
+        }
+    }
+
+    // Adds a note explaining why there's no suggestion when one can't be made.
+    fn can_suggest(self, diag: &mut Diagnostic, app: Applicability) -> bool {
+        if self.too_long {
+            diag.note("the chain of reference operations is too long to suggest a replacement");
+            false
+        } else if self.machine_applicable_only && app != Applicability::MachineApplicable {
+            note_omitted_suggestion(diag);
+            false
+        } else {
+            true
+        }
+    }
+}
+
+// Checks if the expression comes from a different file than its enclosing body. e.g. when it's
+// expanded from `include!`, which doesn't mark its output as coming from an expansion.
+fn is_in_other_file(cx: &LateContext<'_>, e: &Expr<'_>) -> bool {
+    let sm = cx.sess().source_map();
+    let owner_span = cx.tcx.def_span(cx.tcx.hir().enclosing_body_owner(e.hir_id));
+    sm.lookup_source_file(e.span.lo()).start_pos != sm.lookup_source_file(owner_span.lo()).start_pos
+}
+
+// Immutable reborrows are already handled by `borrow_deref_ref`. e.g. `&*x` where `x: &T`
+fn is_linted_by_borrow_deref_ref(cx: &LateContext<'_>, typeck: &TypeckResults<'_>, e: &Expr<'_>) -> bool {
+    if let ExprKind::AddrOf(_, Mutability::Not, inner) = e.kind
+        && let ExprKind::Unary(UnOp::Deref, target) = inner.kind
+        && !matches!(target.kind, ExprKind::Unary(UnOp::Deref, _))
+        && let ty::Ref(_, _, Mutability::Not) = *typeck.expr_ty(target).kind()
+    {
+        !is_lint_allowed(cx, BORROW_DEREF_REF, e.hir_id)
+    } else {
+        false
+    }
+}
+
+// When only machine applicable suggestions are wanted, any other suggestion is left out.
+fn suggest_or_note(
+    diag: &mut Diagnostic,
+    span: Span,
+    msg: &'static str,
+    sugg: String,
+    app: Applicability,
+    machine_applicable_only: bool,
+) {
+    if machine_applicable_only && app != Applicability::MachineApplicable {
+        note_omitted_suggestion(diag);
+    } else {
+        diag.span_suggestion(span, msg, sugg, app);
+    }
+}
+
+// Explains why the suggestion can't be applied automatically. Nothing is added when the
+// applicability was lowered for another reason. e.g. the snippet came from a macro
+fn help_maybe_incorrect(diag: &mut Diagnostic, app: Applicability, reason: Option<MaybeIncorrectReason>) {
+    if app == Applicability::MaybeIncorrect
+        && let Some(reason) = reason
+    {
+        diag.help(reason.help());
+    }
+}
+
+fn note_omitted_suggestion(diag: &mut Diagnostic) {
+    diag.note("a suggestion was omitted as it can't be applied automatically");
+}
+
+/// Checks if the result of a chain of `deref` method calls initializes a `let` statement with an
+/// explicit type which the receiver can be coerced to. e.g. `let s: &str = x.deref();`
+fn is_deref_coerced_let_init<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    receiver: &Expr<'tcx>,
+    e: &Expr<'tcx>,
+    mutbl: Mutability,
+) -> bool {
+    if let Some(Node::Local(local)) = get_parent_node(cx.tcx, e.hir_id)
+        && let Some(ty) = local.ty
+        && !ty_contains_infer(ty)
+        && local.init.is_some_and(|init| init.hir_id == e.hir_id)
+        // Any further coercion could happen on the receiver's type instead.
+        && typeck.expr_ty_adjusted(e) == typeck.expr_ty(e)
+        && let ty::Ref(_, inner_ty, receiver_mutbl) = *typeck.expr_ty(receiver).kind()
+    {
+        match mutbl {
+            Mutability::Not => true,
+            Mutability::Mut => receiver_mutbl == Mutability::Mut && !inner_ty.is_ref(),
+        }
+    } else {
+        false
+    }
+}
+
+/// Gets the method of `Vec`, `String`, `PathBuf` or `OsString` which borrows the receiver of a
+/// `deref` call as a slice when the result isn't used at a coercion site. e.g. `let s = v.deref();`
+fn slice_method_for_deref<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    receiver: &Expr<'tcx>,
+    e: &'tcx Expr<'tcx>,
+    mutbl: Mutability,
+    msrv: &Msrv,
+    stability_cache: &mut StabilityCache<'tcx>,
+) -> Option<&'static str> {
+    let receiver_ty = typeck.expr_ty(receiver);
+    // Temporaries borrowed by `&*` may have their lifetime extended, method receivers don't.
+    if !receiver.is_syntactic_place_expr() && !receiver_ty.is_ref() {
+        return None;
+    }
+    // The call may only remove a reference. e.g. `x.deref()` where `x: &&String`
+    if !matches!(
+        typeck.expr_ty(e).kind(),
+        ty::Ref(_, target, _) if matches!(target.kind(), ty::Slice(_) | ty::Str)
+            || matches!(get_type_diagnostic_name(cx, *target), Some(sym::Path | sym::OsStr))
+    ) {
+        return None;
+    }
+    let receiver_ty = receiver_ty.peel_refs();
+    let method = if is_type_diagnostic_item(cx, receiver_ty, sym::Vec) && msrv.meets(msrvs::VEC_AS_SLICE) {
+        match mutbl {
+            Mutability::Not => "as_slice",
+            Mutability::Mut => "as_mut_slice",
+        }
+    } else if is_type_lang_item(cx, receiver_ty, LangItem::String) && msrv.meets(msrvs::STRING_AS_STR) {
+        match mutbl {
+            Mutability::Not => "as_str",
+            Mutability::Mut => "as_mut_str",
+        }
+    } else if mutbl == Mutability::Not && is_type_diagnostic_item(cx, receiver_ty, sym::PathBuf) {
+        "as_path"
+    } else if mutbl == Mutability::Not && is_type_diagnostic_item(cx, receiver_ty, sym::OsString) {
+        "as_os_str"
+    } else {
+        return None;
+    };
+
+    if let Some(use_cx) = expr_use_ctxt(cx, e)
+        && TyCoercionStability::for_use_cx(cx, &use_cx, stability_cache).is_deref_stable()
+    {
+        None
+    } else {
+        Some(method)
     }
 }
 
+// Removes all layers of parentheses which enclose the whole snippet. e.g. `((x))` -> `x`
+fn strip_enclosing_parens(mut snip: &str) -> &str {
+    while has_enclosing_paren(snip) {
+        snip = snip[1..snip.len() - 1].trim();
+    }
+    snip
+}
+
 impl<'tcx> Dereferencing<'tcx> {
     fn check_local_usage(&mut self, cx: &LateContext<'tcx>, e: &Expr<'tcx>, local: HirId) {
         if let Some(outer_pat) = self.ref_locals.get_mut(&local) {
@@ -1151,6 +2673,9 @@ fn check_local_usage(&mut self, cx: &LateContext<'tcx>, e: &Expr<'tcx>, local: H
                             let snip = snippet_with_context(cx, e.span, span.ctxt(), "..", &mut pat.app).0;
                             pat.replacements.push((span, snip.into()));
                         },
+                        // Borrowing the binding would borrow the closure's copy of it, which can't
+                        // escape the closure. e.g. `move || &x`
+                        _ if is_moved_into_closure(cx, e, local) => *outer_pat = None,
                         Some(parent) if !parent.span.from_expansion() => {
                             // Double reference might be needed at this point.
                             if parent.precedence().order() == PREC_POSTFIX {
@@ -1177,4 +2702,154 @@ fn check_local_usage(&mut self, cx: &LateContext<'tcx>, e: &Expr<'tcx>, local: H
             }
         }
     }
+
+    /// Checks for explicit derefs of the value being indexed, which indexing would do
+    /// automatically. e.g. `(&mut *x)[i]` where `x: &mut Vec<_>`
+    fn check_index_base(&self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        let ExprKind::Index(base, idx, _) = expr.kind else {
+            return;
+        };
+        let typeck = cx.typeck_results();
+        // `&x[..]` is handled as a deref of `x`.
+        if !base.span.eq_ctxt(expr.span) || self.try_parse_full_slice(cx, expr).is_some() {
+            return;
+        }
+        let mut inner = match base.kind {
+            ExprKind::AddrOf(BorrowKind::Ref, _, sub_expr) => sub_expr,
+            _ => base,
+        };
+        let mut deref_count = 0;
+        while let ExprKind::Unary(UnOp::Deref, sub_expr) = inner.kind
+            && inner.span.eq_ctxt(expr.span)
+            && !typeck.expr_ty(sub_expr).is_unsafe_ptr()
+        {
+            inner = sub_expr;
+            deref_count += 1;
+        }
+        if deref_count == 0
+            || !is_index_lookup_stable(cx, typeck.expr_ty(inner), typeck.expr_ty(base), typeck.expr_ty(idx))
+            || derefs_through_manually_drop(cx, typeck, base, inner)
+            || derefs_interior_mut(cx, typeck, base)
+        {
+            return;
+        }
+        if is_linted_by_borrow_deref_ref(cx, typeck, base) {
+            return;
+        }
+
+        span_lint_hir_and_then(
+            cx,
+            EXPLICIT_AUTO_DEREF,
+            base.hir_id,
+            base.span,
+            "deref which would be done by auto-deref",
+            |diag| {
+                let mut app = Applicability::MachineApplicable;
+                let (snip, snip_is_macro) = snippet_with_context(cx, inner.span, base.span.ctxt(), "..", &mut app);
+                let sugg = if !snip_is_macro && inner.precedence().order() < PREC_POSTFIX && !has_enclosing_paren(&snip)
+                {
+                    format!("({snip})")
+                } else {
+                    snip.into_owned()
+                };
+                ChainSugg::new(cx, typeck, base, self).suggest(diag, base.span, "try", sugg, app);
+            },
+        );
+    }
+
+    /// Checks for comparisons which dereference nested references on both sides, when the
+    /// references can be compared directly. e.g. `**a == **b` where `a, b: &&str`
+    fn check_comparison(&self, cx: &LateContext<'tcx>, expr: &Expr<'_>, lhs: &'tcx Expr<'tcx>, rhs: &'tcx Expr<'tcx>) {
+        let Some((l, r)) = peel_comparison_derefs(cx, expr, lhs, rhs) else {
+            return;
+        };
+        span_lint_hir_and_then(
+            cx,
+            EXPLICIT_AUTO_DEREF,
+            expr.hir_id,
+            expr.span,
+            "deref which would be done by the comparison",
+            |diag| {
+                let mut app = Applicability::MachineApplicable;
+                let mut sugg = |e: &Expr<'_>| {
+                    let snip = snippet_with_context(cx, e.span, expr.span.ctxt(), "..", &mut app).0;
+                    if e.precedence().order() < PREC_PREFIX && !has_enclosing_paren(&snip) {
+                        format!("({snip})")
+                    } else {
+                        snip.into_owned()
+                    }
+                };
+                let sugg = vec![(lhs.span, sugg(l)), (rhs.span, sugg(r))];
+                // Both sides have the same number of derefs.
+                ChainSugg::new(cx, cx.typeck_results(), lhs, self).multipart_suggest(
+                    diag,
+                    "compare the references directly",
+                    sugg,
+                    app,
+                );
+            },
+        );
+    }
+
+    /// Checks for explicit derefs in a borrowed value which is matched against patterns. Match
+    /// ergonomics peel off every reference, so the derefs can be removed as long as the patterns
+    /// bind the same way. e.g. `if let Some(x) = &**y` where `y: &&Option<_>`
+    fn check_scrutinee(&self, cx: &LateContext<'tcx>, scrutinee: &'tcx Expr<'tcx>, pats: &[&Pat<'_>]) {
+        let ExprKind::AddrOf(BorrowKind::Ref, mutbl, borrowed) = scrutinee.kind else {
+            return;
+        };
+        let typeck = cx.typeck_results();
+        if scrutinee.span.from_expansion() || !pats.iter().all(|pat| pat_peels_refs(cx, pat)) {
+            return;
+        }
+        // Match ergonomics don't see through smart pointers, so only derefs of references can be
+        // removed.
+        let mut inner = borrowed;
+        let mut deref_count = 0;
+        while let ExprKind::Unary(UnOp::Deref, sub_expr) = inner.kind
+            && inner.span.eq_ctxt(scrutinee.span)
+            && typeck.expr_ty(sub_expr).is_ref()
+        {
+            inner = sub_expr;
+            deref_count += 1;
+        }
+        // The bindings would change from `ref` to `ref mut`, or the reverse.
+        let has_shared_ref = |mut ty: Ty<'tcx>| {
+            while let ty::Ref(_, pointee, mutbl) = *ty.kind() {
+                if mutbl == Mutability::Not {
+                    return true;
+                }
+                ty = pointee;
+            }
+            false
+        };
+        if deref_count == 0
+            || (mutbl == Mutability::Not || has_shared_ref(typeck.expr_ty(borrowed)))
+                != has_shared_ref(typeck.expr_ty(inner))
+        {
+            return;
+        }
+        if is_linted_by_borrow_deref_ref(cx, typeck, scrutinee) {
+            return;
+        }
+
+        span_lint_hir_and_then(
+            cx,
+            EXPLICIT_AUTO_DEREF,
+            scrutinee.hir_id,
+            scrutinee.span,
+            "deref which would be done by auto-deref",
+            |diag| {
+                let mut app = Applicability::MachineApplicable;
+                let snip = snippet_with_context(cx, inner.span, scrutinee.span.ctxt(), "..", &mut app).0;
+                ChainSugg::new(cx, typeck, scrutinee, self).suggest(
+                    diag,
+                    scrutinee.span,
+                    "try",
+                    snip.into_owned(),
+                    app,
+                );
+            },
+        );
+    }
 }
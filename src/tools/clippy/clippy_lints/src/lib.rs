@@ -529,6 +529,7 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
         ref await_holding_invalid_types,
         cargo_ignore_publish,
         cognitive_complexity_threshold,
+        deref_suggestions_machine_applicable_only: _,
         ref disallowed_macros,
         ref disallowed_methods,
         ref disallowed_names,
@@ -540,6 +541,10 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
         enum_variant_name_threshold,
         enum_variant_size_threshold,
         excessive_nesting_threshold,
+        explicit_auto_deref_full_slices: _,
+        explicit_deref_methods_check_deref: _,
+        explicit_deref_methods_check_deref_mut: _,
+        explicit_deref_methods_ignore: _,
         future_size_threshold,
         ref ignore_interior_mutability,
         large_error_threshold,
@@ -548,11 +553,14 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
         max_fn_params_bools,
         max_include_file_size,
         max_struct_bools,
+        max_suggested_deref_chain_length: _,
         max_suggested_slice_pattern_length,
         max_trait_bounds,
         min_ident_chars_threshold,
         missing_docs_in_crate_items,
         ref msrv,
+        needless_borrow_allow_parens: _,
+        needless_borrow_coercion_fns: _,
         pass_by_value_size_limit,
         semicolon_inside_block_ignore_singleline,
         semicolon_outside_block_ignore_multiline,
@@ -874,7 +882,7 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
     store.register_late_pass(move |_| Box::new(wildcard_imports::WildcardImports::new(warn_on_all_wildcard_imports)));
     store.register_late_pass(|_| Box::<redundant_pub_crate::RedundantPubCrate>::default());
     store.register_late_pass(|_| Box::new(unnamed_address::UnnamedAddress));
-    store.register_late_pass(|_| Box::<dereference::Dereferencing<'_>>::default());
+    store.register_late_pass(move |_| Box::new(dereference::Dereferencing::new(conf)));
     store.register_late_pass(|_| Box::new(option_if_let_else::OptionIfLetElse));
     store.register_late_pass(|_| Box::new(future_not_send::FutureNotSend));
     store.register_late_pass(move |_| Box::new(large_futures::LargeFuture::new(future_size_threshold)));
@@ -156,7 +156,7 @@ fn path_has_args(p: &QPath<'_>) -> bool {
 /// * The borrowed expression meets all the generic type's constraints.
 /// * The generic type appears only once in the functions signature.
 /// * The borrowed value will not be moved if it is used later in the function.
-#[expect(clippy::too_many_arguments)]
+#[expect(clippy::too_many_arguments, clippy::too_many_lines)]
 fn needless_borrow_count<'tcx>(
     cx: &LateContext<'tcx>,
     possible_borrowers: &mut Vec<(LocalDefId, PossibleBorrowerMap<'tcx, 'tcx>)>,
@@ -213,9 +213,16 @@ fn needless_borrow_count<'tcx>(
     // See:
     // - https://github.com/rust-lang/rust-clippy/pull/9674#issuecomment-1289294201
     // - https://github.com/rust-lang/rust-clippy/pull/9674#issuecomment-1292225232
-    if projection_predicates
+    //
+    // Mixed projections are only allowed directly on the argument's type, and only when removing the
+    // borrow doesn't change the projected type. e.g. `v.splice(.., &mut iter)`
+    let mixed_projection_predicates = projection_predicates
         .iter()
-        .any(|projection_predicate| is_mixed_projection_predicate(cx, fn_id, projection_predicate))
+        .filter(|projection_predicate| is_mixed_projection_predicate(cx, fn_id, projection_predicate))
+        .collect::<Vec<_>>();
+    if mixed_projection_predicates
+        .iter()
+        .any(|projection_predicate| projection_predicate.projection_ty.self_ty() != param_ty.to_ty(cx.tcx))
     {
         return 0;
     }
@@ -234,6 +241,18 @@ fn needless_borrow_count<'tcx>(
 
         let referent_ty = cx.typeck_results().expr_ty(referent);
 
+        if !mixed_projection_predicates.iter().all(|projection_predicate| {
+            projects_to_same_ty(
+                cx,
+                projection_predicate,
+                callee_args,
+                cx.typeck_results().expr_ty(reference),
+                referent_ty,
+            )
+        }) {
+            return false;
+        }
+
         if !is_copy(cx, referent_ty)
             && (referent_ty.has_significant_drop(cx.tcx, cx.param_env)
                 || !referent_used_exactly_once(cx, possible_borrowers, reference))
@@ -339,6 +358,24 @@ fn is_mixed_projection_predicate<'tcx>(
     }
 }
 
+// Checks whether projecting from both types through the given predicate results in the same type.
+fn projects_to_same_ty<'tcx>(
+    cx: &LateContext<'tcx>,
+    projection_predicate: &ProjectionPredicate<'tcx>,
+    callee_args: &'tcx List<GenericArg<'tcx>>,
+    ty: Ty<'tcx>,
+    new_ty: Ty<'tcx>,
+) -> bool {
+    let projection_ty = EarlyBinder::bind(projection_predicate.projection_ty).instantiate(cx.tcx, callee_args);
+    let project = |self_ty| {
+        let projection = cx
+            .tcx
+            .mk_ty_from_kind(ty::Alias(ty::Projection, projection_ty.with_self_ty(cx.tcx, self_ty)));
+        cx.tcx.try_normalize_erasing_regions(cx.param_env, projection).ok()
+    };
+    matches!((project(ty), project(new_ty)), (Some(ty), Some(new_ty)) if ty == new_ty)
+}
+
 fn referent_used_exactly_once<'tcx>(
     cx: &LateContext<'tcx>,
     possible_borrowers: &mut Vec<(LocalDefId, PossibleBorrowerMap<'tcx, 'tcx>)>,
@@ -1,7 +1,8 @@
+use crate::dereference::{peel_comparison_derefs, EXPLICIT_AUTO_DEREF};
 use clippy_utils::diagnostics::{multispan_sugg, span_lint_and_then};
-use clippy_utils::get_enclosing_block;
 use clippy_utils::source::snippet;
 use clippy_utils::ty::{implements_trait, is_copy};
+use clippy_utils::{get_enclosing_block, is_lint_allowed};
 use rustc_errors::Applicability;
 use rustc_hir::def::Res;
 use rustc_hir::def_id::DefId;
@@ -43,6 +44,13 @@ pub(crate) fn check<'tcx>(
             (&ExprKind::Lit(..), _) | (_, &ExprKind::Lit(..)) => {},
             // &foo == &bar
             (&ExprKind::AddrOf(BorrowKind::Ref, _, l), &ExprKind::AddrOf(BorrowKind::Ref, _, r)) => {
+                // `explicit_auto_deref` removes the borrows along with the derefs. e.g. `&**a == &**b`
+                if matches!(op, BinOpKind::Eq | BinOpKind::Ne)
+                    && !is_lint_allowed(cx, EXPLICIT_AUTO_DEREF, e.hir_id)
+                    && peel_comparison_derefs(cx, e, left, right).is_some()
+                {
+                    return;
+                }
                 let lty = cx.typeck_results().expr_ty(l);
                 let rty = cx.typeck_results().expr_ty(r);
                 let lcpy = is_copy(cx, lty);
@@ -88,9 +88,9 @@
 use rustc_hir::LangItem::{OptionNone, OptionSome, ResultErr, ResultOk};
 use rustc_hir::{
     self as hir, def, Arm, ArrayLen, BindingAnnotation, Block, BlockCheckMode, Body, Closure, Destination, Expr,
-    ExprField, ExprKind, FnDecl, FnRetTy, GenericArgs, HirId, Impl, ImplItem, ImplItemKind, ImplItemRef, Item,
-    ItemKind, LangItem, Local, MatchSource, Mutability, Node, OwnerId, Param, Pat, PatKind, Path, PathSegment, PrimTy,
-    QPath, Stmt, StmtKind, TraitItem, TraitItemKind, TraitItemRef, TraitRef, TyKind, UnOp,
+    ExprField, ExprKind, FnDecl, FnRetTy, GenericArg, GenericArgs, HirId, Impl, ImplItem, ImplItemKind, ImplItemRef,
+    Item, ItemKind, LangItem, Local, MatchSource, Mutability, Node, OwnerId, Param, Pat, PatKind, Path, PathSegment,
+    PrimTy, QPath, Stmt, StmtKind, TraitItem, TraitItemKind, TraitItemRef, TraitRef, TyKind, UnOp,
 };
 use rustc_lexer::{tokenize, TokenKind};
 use rustc_lint::{LateContext, Level, Lint, LintContext};
@@ -107,12 +107,12 @@
 use rustc_span::hygiene::{ExpnKind, MacroKind};
 use rustc_span::source_map::SourceMap;
 use rustc_span::symbol::{kw, Ident, Symbol};
-use rustc_span::{sym, Span};
+use rustc_span::{sym, Span, SyntaxContext};
 use rustc_target::abi::Integer;
 use visitors::Visitable;
 
 use crate::consts::{constant, mir_to_const, Constant};
-use crate::higher::Range;
+use crate::higher::{Range, VecArgs};
 use crate::ty::{
     adt_and_variant_of_res, can_partially_move_ty, expr_sig, is_copy, is_recursively_primitive_type,
     ty_is_fn_once_param,
@@ -2620,7 +2620,7 @@ pub fn walk_to_expr_usage<'tcx, T>(
                 child_id = id;
                 iter = map.parent_iter(id);
             },
-            ExprKind::Block(..) => child_id = parent_id,
+            ExprKind::Block(..) | ExprKind::DropTemps(_) => child_id = parent_id,
             _ => return None,
         }
     }
@@ -2667,6 +2667,14 @@ pub enum ExprUseNode<'tcx> {
     Callee,
     /// Access of a field.
     FieldAccess(Ident),
+    /// The element of an array repeat expression or of `vec![elem; len]`. Contains the repeat
+    /// expression and the context its use should be searched for in.
+    RepeatElem(&'tcx Expr<'tcx>, SyntaxContext),
+    /// An element of a tuple expression. Contains the tuple expression, the index of the element
+    /// and the context its use should be searched for in.
+    TupleElem(&'tcx Expr<'tcx>, usize, SyntaxContext),
+    /// The right-hand side of a compound assignment. e.g. `x += y`
+    AssignOpRhs(&'tcx Expr<'tcx>),
 }
 impl<'tcx> ExprUseNode<'tcx> {
     /// Checks if the value is returned from the function.
@@ -2680,6 +2688,7 @@ pub fn is_recv(&self) -> bool {
     }
 
     /// Gets the needed type as it's defined without any type inference.
+    #[expect(clippy::too_many_lines)]
     pub fn defined_ty(&self, cx: &LateContext<'tcx>) -> Option<DefinedTy<'tcx>> {
         match *self {
             Self::Local(Local { ty: Some(ty), .. }) => Some(DefinedTy::Hir(ty)),
@@ -2695,6 +2704,16 @@ pub fn defined_ty(&self, cx: &LateContext<'tcx>) -> Option<DefinedTy<'tcx>> {
                 })) = cx.tcx.opt_hir_node(hir_id)
                 {
                     match c.fn_decl.output {
+                        // The body of an `async fn` returns the output of the function's future.
+                        FnRetTy::DefaultReturn(_)
+                            if c.kind
+                                == hir::ClosureKind::Coroutine(hir::CoroutineKind::Desugared(
+                                    hir::CoroutineDesugaring::Async,
+                                    hir::CoroutineSource::Fn,
+                                )) =>
+                        {
+                            async_fn_output_ty(cx, cx.tcx.parent(c.def_id.to_def_id()))
+                        },
                         FnRetTy::DefaultReturn(_) => None,
                         FnRetTy::Return(ty) => Some(DefinedTy::Hir(ty)),
                     }
@@ -2743,18 +2762,109 @@ pub fn defined_ty(&self, cx: &LateContext<'tcx>) -> Option<DefinedTy<'tcx>> {
                 let sig = cx.tcx.fn_sig(id).skip_binder();
                 Some(DefinedTy::Mir(cx.tcx.param_env(id).and(sig.input(i))))
             },
+            Self::RepeatElem(e, ctxt) => match expr_use_ctxt_in(cx, e, ctxt)?.node.defined_ty(cx)? {
+                DefinedTy::Hir(ty) => match ty.kind {
+                    TyKind::Array(ty, _) => Some(DefinedTy::Hir(ty)),
+                    TyKind::Path(QPath::Resolved(None, path))
+                        if path
+                            .res
+                            .opt_def_id()
+                            .is_some_and(|id| cx.tcx.is_diagnostic_item(sym::Vec, id)) =>
+                    {
+                        path.segments.last()?.args?.args.iter().find_map(|arg| match arg {
+                            GenericArg::Type(ty) => Some(DefinedTy::Hir(ty)),
+                            _ => None,
+                        })
+                    },
+                    _ => None,
+                },
+                DefinedTy::Mir(ty) => match *ty.value.skip_binder().kind() {
+                    rustc_ty::Array(elem_ty, _) => Some(DefinedTy::Mir(ty.param_env.and(ty.value.rebind(elem_ty)))),
+                    rustc_ty::Adt(adt, args) if cx.tcx.is_diagnostic_item(sym::Vec, adt.did()) => {
+                        Some(DefinedTy::Mir(ty.param_env.and(ty.value.rebind(args.type_at(0)))))
+                    },
+                    _ => None,
+                },
+            },
+            Self::TupleElem(e, i, ctxt) => match expr_use_ctxt_in(cx, e, ctxt)?.node.defined_ty(cx)? {
+                DefinedTy::Hir(ty) => match ty.kind {
+                    TyKind::Tup(tys) => tys.get(i).map(DefinedTy::Hir),
+                    _ => None,
+                },
+                DefinedTy::Mir(ty) => match *ty.value.skip_binder().kind() {
+                    rustc_ty::Tuple(tys) => tys
+                        .get(i)
+                        .map(|&elem_ty| DefinedTy::Mir(ty.param_env.and(ty.value.rebind(elem_ty)))),
+                    _ => None,
+                },
+            },
+            Self::AssignOpRhs(e) => assign_op_rhs_ty(cx, e),
             Self::Local(_) | Self::FieldAccess(..) | Self::Callee => None,
         }
     }
 }
 
+/// Gets the type the future returned by an `async fn` resolves to.
+fn async_fn_output_ty<'tcx>(cx: &LateContext<'tcx>, fn_id: DefId) -> Option<DefinedTy<'tcx>> {
+    let output = cx.tcx.fn_sig(fn_id).instantiate_identity().output();
+    let rustc_ty::Alias(rustc_ty::Opaque, alias) = *output.skip_binder().kind() else {
+        return None;
+    };
+    let future_id = cx.tcx.lang_items().future_trait()?;
+    cx.tcx
+        .explicit_item_bounds(alias.def_id)
+        .iter_instantiated_copied(cx.tcx, alias.args)
+        .find_map(|(clause, _)| match clause.kind().skip_binder() {
+            rustc_ty::ClauseKind::Projection(p) if p.projection_ty.trait_def_id(cx.tcx) == future_id => p.term.ty(),
+            _ => None,
+        })
+        .map(|ty| DefinedTy::Mir(cx.tcx.param_env(fn_id).and(output.rebind(ty))))
+}
+
+/// Gets the type the right-hand side of a compound assignment is coerced to, if any.
+fn assign_op_rhs_ty<'tcx>(cx: &LateContext<'tcx>, e: &Expr<'_>) -> Option<DefinedTy<'tcx>> {
+    let ExprKind::AssignOp(_, lhs, _) = e.kind else {
+        return None;
+    };
+    let typeck = cx.typeck_results();
+    // Only overloaded operators have a method. Builtin operators never coerce their operands.
+    let id = typeck.type_dependent_def_id(e.hir_id)?;
+    let trait_id = cx.tcx.trait_of_item(id)?;
+    let lhs_ty = typeck.expr_ty(lhs);
+    if lhs_ty.has_param() {
+        return None;
+    }
+    // The right-hand side is only coerced when a single impl determines its type.
+    let mut impls = Vec::new();
+    cx.tcx
+        .for_each_relevant_impl(trait_id, lhs_ty, |impl_id| impls.push(impl_id));
+    if let [impl_id] = *impls
+        && let Some(trait_ref) = cx.tcx.impl_trait_ref(impl_id)
+        && !trait_ref.instantiate_identity().args.type_at(1).has_non_region_param()
+    {
+        let sig = cx.tcx.fn_sig(id).instantiate(cx.tcx, typeck.node_args(e.hir_id));
+        Some(DefinedTy::Mir(cx.param_env.and(sig.input(1))))
+    } else {
+        None
+    }
+}
+
 /// Gets the context an expression's value is used in.
-#[expect(clippy::too_many_lines)]
 pub fn expr_use_ctxt<'tcx>(cx: &LateContext<'tcx>, e: &'tcx Expr<'tcx>) -> Option<ExprUseCtxt<'tcx>> {
+    expr_use_ctxt_in(cx, e, e.span.ctxt())
+}
+
+/// Gets the context an expression's value is used in. Only parent nodes from the given syntax
+/// context are considered to be a use of the value.
+#[expect(clippy::too_many_lines)]
+fn expr_use_ctxt_in<'tcx>(
+    cx: &LateContext<'tcx>,
+    e: &'tcx Expr<'tcx>,
+    ctxt: SyntaxContext,
+) -> Option<ExprUseCtxt<'tcx>> {
     let mut adjustments = [].as_slice();
     let mut is_ty_unified = false;
     let mut moved_before_use = false;
-    let ctxt = e.span.ctxt();
     walk_to_expr_usage(cx, e, &mut |parent, child_id| {
         // LocalTableInContext returns the wrong lifetime, so go use `expr_adjustments` instead.
         if adjustments.is_empty()
@@ -2824,6 +2934,19 @@ pub fn expr_use_ctxt<'tcx>(cx: &LateContext<'tcx>, e: &'tcx Expr<'tcx>) -> Optio
                 moved_before_use,
             }),
 
+            Node::Expr(parent)
+                if let Some(VecArgs::Repeat(elem, _)) = VecArgs::hir(cx, parent)
+                    && elem.hir_id == child_id
+                    && elem.span.ctxt() == ctxt =>
+            {
+                Some(ExprUseCtxt {
+                    node: ExprUseNode::RepeatElem(parent, ctxt),
+                    adjustments,
+                    is_ty_unified,
+                    moved_before_use,
+                })
+            },
+
             Node::Expr(parent) if parent.span.ctxt() == ctxt => match parent.kind {
                 ExprKind::Ret(_) => Some(ExprUseCtxt {
                     node: ExprUseNode::Return(OwnerId {
@@ -2858,6 +2981,27 @@ pub fn expr_use_ctxt<'tcx>(cx: &LateContext<'tcx>, e: &'tcx Expr<'tcx>) -> Optio
                     is_ty_unified,
                     moved_before_use,
                 }),
+                ExprKind::Repeat(..) => Some(ExprUseCtxt {
+                    node: ExprUseNode::RepeatElem(parent, ctxt),
+                    adjustments,
+                    is_ty_unified,
+                    moved_before_use,
+                }),
+                ExprKind::Tup(elems) => elems
+                    .iter()
+                    .position(|elem| elem.hir_id == child_id)
+                    .map(|i| ExprUseCtxt {
+                        node: ExprUseNode::TupleElem(parent, i, ctxt),
+                        adjustments,
+                        is_ty_unified,
+                        moved_before_use,
+                    }),
+                ExprKind::AssignOp(_, _, rhs) if rhs.hir_id == child_id => Some(ExprUseCtxt {
+                    node: ExprUseNode::AssignOpRhs(parent),
+                    adjustments,
+                    is_ty_unified,
+                    moved_before_use,
+                }),
                 ExprKind::Field(child, name) if child.hir_id == e.hir_id => Some(ExprUseCtxt {
                     node: ExprUseNode::FieldAccess(name),
                     adjustments,
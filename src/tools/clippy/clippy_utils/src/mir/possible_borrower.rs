@@ -116,10 +116,13 @@ fn visit_terminator(&mut self, terminator: &mir::Terminator<'_>, _loc: mir::Loca
                 }
             }
 
+            // A mutably borrowed variable can only store a borrow if its type has lifetimes.
+            // e.g. `v.extend(&mut iter)` with `v: Vec<u32>` can't make `v` a borrower of `iter`.
             let mut mutable_variables: Vec<mir::Local> = mutable_borrowers
                 .iter()
                 .filter_map(|r| self.possible_origin.get(r))
                 .flat_map(HybridBitSet::iter)
+                .filter(|&local| ContainsRegion.visit_ty(self.body.local_decls[local].ty).is_break())
                 .collect();
 
             if ContainsRegion.visit_ty(self.body.local_decls[*dest].ty).is_break() {
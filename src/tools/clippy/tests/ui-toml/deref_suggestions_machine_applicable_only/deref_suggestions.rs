@@ -0,0 +1,16 @@
+//@no-rustfix
+#![warn(clippy::explicit_auto_deref)]
+#![allow(clippy::borrowed_box)]
+
+// The target of the auto-deref depends on type inference. No suggestion is made.
+fn deref_to_u<U, T: core::ops::Deref<Target = U>>(x: &T) -> &U {
+    &**x
+    //~^ ERROR: deref which would be done by auto-deref
+}
+
+fn concrete(x: &Box<Vec<u32>>) -> &Vec<u32> {
+    &**x
+    //~^ ERROR: deref which would be done by auto-deref
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+#![warn(clippy::explicit_auto_deref)]
+#![allow(clippy::needless_borrow, clippy::useless_vec)]
+
+fn main() {
+    use std::ops::{Deref, Index, RangeFull};
+
+    fn takes_slice(_: &[u8]) {}
+    fn takes_mut_slice(_: &mut [u8]) {}
+    fn takes_str(_: &str) {}
+    fn takes_ref<T: ?Sized>(_: &T) {}
+
+    let mut v = vec![0u8];
+    let s = String::new();
+    let b: Box<[u8]> = Box::new([0]);
+
+    takes_mut_slice(&mut v[..]);
+    let r = &v;
+    takes_slice(&v[..]);
+    takes_str(&s[..]);
+    takes_slice(&b[..]);
+    takes_slice(&(*r)[..]);
+    let _: &[u8] = &v[..];
+
+    // Don't lint. Indexing auto-derefs `r`
+    takes_slice(&r[..]);
+    // Don't lint. Arrays are unsized rather than dereferenced
+    takes_slice(&[0u8; 1][..]);
+    // Don't lint. The type is inferred from the slice
+    takes_ref(&v[..]);
+    let _ = &v[..];
+    // Don't lint. Not a full range
+    takes_slice(&v[1..]);
+
+    struct S(Vec<u8>);
+    impl Deref for S {
+        type Target = [u8];
+        fn deref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+    impl Index<RangeFull> for S {
+        type Output = Vec<u8>;
+        fn index(&self, _: RangeFull) -> &Vec<u8> {
+            &self.0
+        }
+    }
+    // Don't lint. `Index` returns something other than the `Deref` target
+    let _: &Vec<u8> = &S(Vec::new())[..];
+}
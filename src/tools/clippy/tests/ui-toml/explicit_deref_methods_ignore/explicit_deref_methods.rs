@@ -0,0 +1,70 @@
+#![warn(clippy::explicit_deref_methods)]
+#![allow(clippy::explicit_auto_deref)]
+
+use std::ops::{Deref, DerefMut};
+
+pub struct MyGuard(String);
+
+impl Deref for MyGuard {
+    type Target = String;
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl DerefMut for MyGuard {
+    fn deref_mut(&mut self) -> &mut String {
+        &mut self.0
+    }
+}
+
+pub struct Unqualified(String);
+
+impl Deref for Unqualified {
+    type Target = String;
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+pub mod wrappers {
+    use std::ops::Deref;
+
+    pub struct Wrapper(pub String);
+
+    impl Deref for Wrapper {
+        type Target = String;
+        fn deref(&self) -> &String {
+            &self.0
+        }
+    }
+
+    pub struct NotIgnored(pub String);
+
+    impl Deref for NotIgnored {
+        type Target = String;
+        fn deref(&self) -> &String {
+            &self.0
+        }
+    }
+}
+
+use wrappers::{NotIgnored, Wrapper};
+
+fn main() {
+    let mut guard = MyGuard(String::new());
+    let _: &String = guard.deref();
+    let _: &mut String = guard.deref_mut();
+    let r = &guard;
+    let _: &String = r.deref();
+
+    let wrapper = Wrapper(String::new());
+    let _: &String = wrapper.deref();
+
+    let not_ignored = NotIgnored(String::new());
+    let _: &String = not_ignored.deref();
+
+    // Paths have to include the crate name
+    let unqualified = Unqualified(String::new());
+    let _: &String = unqualified.deref();
+}
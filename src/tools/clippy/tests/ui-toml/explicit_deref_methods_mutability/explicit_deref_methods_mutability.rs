@@ -0,0 +1,33 @@
+//@revisions: both deref deref_mut neither
+//@[both] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/explicit_deref_methods_mutability/both
+//@[deref] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/explicit_deref_methods_mutability/deref
+//@[deref_mut] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/explicit_deref_methods_mutability/deref_mut
+//@[neither] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/explicit_deref_methods_mutability/neither
+
+#![warn(clippy::explicit_deref_methods)]
+#![allow(clippy::explicit_auto_deref)]
+
+use std::ops::{Deref, DerefMut};
+
+struct Wrapper(String);
+
+impl Deref for Wrapper {
+    type Target = String;
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl DerefMut for Wrapper {
+    fn deref_mut(&mut self) -> &mut String {
+        &mut self.0
+    }
+}
+
+fn main() {
+    let mut w = Wrapper(String::new());
+    let _ = w.deref();
+    let _: &String = w.deref();
+    let _ = w.deref_mut();
+    let _: &mut String = w.deref_mut();
+}
@@ -0,0 +1,40 @@
+//@no-rustfix
+#![warn(clippy::explicit_auto_deref, clippy::needless_borrow)]
+#![allow(clippy::borrow_deref_ref, clippy::deref_addrof)]
+
+use std::fmt::{self, Write};
+
+fn f(_: &String) {}
+
+fn main() {
+    let s = String::new();
+    let r = &&s;
+
+    let _: &String = &**r;
+    //~^ ERROR: deref which would be done by auto-deref
+    f(&&&&s);
+    //~^ ERROR: this expression creates a reference which is immediately dereferenced
+
+    // Only the lints are emitted for longer chains
+    let _: &String = &*&*&*&*&*&*&*&*&*&*&*&*&*&*&*&*&*&*&*&**r;
+    //~^ ERROR: deref which would be done by auto-deref
+    f(&&&&&s);
+    //~^ ERROR: this expression creates a reference which is immediately dereferenced
+}
+
+fn other_positions(a: &&&&&&str, b: &&&&&&str, v: &&&&&Vec<u8>, o: &&&&&Option<u8>) {
+    let _ = *****a == *****b;
+    //~^ ERROR: deref which would be done by the comparison
+    let _ = (&*****v)[0];
+    //~^ ERROR: deref which would be done by auto-deref
+    if let Some(_x) = &*****o {}
+    //~^ ERROR: deref which would be done by auto-deref
+    assert_eq!(&&&&&a, &&&&&b);
+    //~^ ERROR: this expression borrows a value the compiler would automatically borrow
+    //~| ERROR: this expression borrows a value the compiler would automatically borrow
+}
+
+fn write_arg(w: &mut String, x: &&&&&&str) -> fmt::Result {
+    write!(w, "{}", *****x)
+    //~^ ERROR: deref which would be done by auto-deref
+}
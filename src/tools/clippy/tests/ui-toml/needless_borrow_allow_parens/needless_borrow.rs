@@ -0,0 +1,25 @@
+#![warn(clippy::needless_borrow)]
+
+trait Foo {
+    fn foo(self);
+}
+impl Foo for &'_ () {
+    fn foo(self) {}
+}
+
+struct S<F: Fn()> {
+    f: F,
+}
+
+fn f(_: &()) {}
+
+fn main() {
+    let s = S { f: || {} };
+
+    // Don't lint. The suggestions would need parentheses
+    (&&()).foo();
+    (&s.f)();
+
+    // Lint
+    f(&&());
+}
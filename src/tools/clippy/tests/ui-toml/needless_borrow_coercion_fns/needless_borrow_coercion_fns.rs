@@ -0,0 +1,38 @@
+#![warn(clippy::needless_borrow, clippy::explicit_auto_deref)]
+#![allow(clippy::borrow_deref_ref)]
+
+use std::fmt::Display;
+
+fn show<T: Display + ?Sized>(_: &T) {}
+fn show_mut<T: Display + ?Sized>(_: &mut T) {}
+fn other<T: Display + ?Sized>(_: &T) {}
+
+mod api {
+    pub struct Printer;
+    impl Printer {
+        pub fn print<T: std::fmt::Display + ?Sized>(&self, _: &T) {}
+    }
+}
+
+fn main() {
+    let s = String::new();
+    let r = &s;
+    let rr = &r;
+    let p = api::Printer;
+
+    show(&**rr);
+    show(&&s);
+    show(&&&s);
+    show(&r);
+    p.print(&**rr);
+    p.print(&&s);
+
+    let mut m = String::new();
+    show_mut(&mut &mut m);
+    // Don't lint. The mutable borrow can't be removed from a shared reference
+    show_mut(&mut &s);
+
+    // Don't lint. The function is listed without its crate name
+    other(&**rr);
+    other(&&s);
+}
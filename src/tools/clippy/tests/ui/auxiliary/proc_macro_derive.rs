@@ -169,3 +169,39 @@ pub fn b(&self) {
         }
     }
 }
+
+fn respan(stream: TokenStream, span: Span) -> TokenStream {
+    stream
+        .into_iter()
+        .map(|mut tt| {
+            if let TokenTree::Group(g) = &tt {
+                tt = Group::new(g.delimiter(), respan(g.stream(), span)).into();
+            }
+            tt.set_span(span);
+            tt
+        })
+        .collect()
+}
+
+#[proc_macro_derive(DerefInDerive)]
+pub fn derive_deref_in_derive(input: TokenStream) -> TokenStream {
+    // Every token is given the span of the type's name, so the output doesn't look like it's from an
+    // expansion.
+    let name = input
+        .into_iter()
+        .find_map(|tt| match tt {
+            TokenTree::Ident(i) if !matches!(&*i.to_string(), "pub" | "struct" | "enum") => Some(i),
+            _ => None,
+        })
+        .unwrap();
+    let span = name.span();
+    let name = TokenTree::Ident(name);
+    let output = quote! {
+        impl $name {
+            fn _deref_in_derive(s: &String) -> usize {
+                (&&s).len() + (*s).len() + (&mut *String::new())[..].len()
+            }
+        }
+    };
+    respan(output, span)
+}
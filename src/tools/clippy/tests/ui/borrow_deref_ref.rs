@@ -1,6 +1,6 @@
 //@aux-build: proc_macros.rs
 
-#![allow(dead_code, unused_variables)]
+#![allow(dead_code, unused_variables, clippy::explicit_auto_deref, clippy::redundant_reborrow)]
 
 extern crate proc_macros;
 use proc_macros::with_span;
@@ -50,6 +50,132 @@ fn main() {
     }
 }
 
+// `needless_borrow` handles the result of a `move` closure
+mod should_not_lint3 {
+    fn main() {
+        let a = &12;
+        let _ = move || &*a;
+    }
+}
+
+// The borrow is discarded, it's unclear whether a reborrow or a deref was intended
+mod should_not_lint4 {
+    fn main() {
+        let a = &String::new();
+        let _ = &*a;
+    }
+}
+
+// `deref_addrof` removes the inner `*&`, leaving a single borrow
+mod deref_addrof {
+    fn f(_: &String) {}
+    fn g(_: &mut String) {}
+
+    fn main() {
+        let mut x = String::new();
+        f(&*&x);
+        g(&mut *&mut x);
+        let _: &String = &*&x;
+    }
+}
+
+// The scrutinee of `matches!` is written by the user
+mod matches_scrutinee {
+    fn main() {
+        let s: &str = "x";
+        let _ = matches!(&*s, "x");
+        let _ = matches!(&*s, "x" | "y" if s.len() == 1);
+        assert!(matches!(&*s, "x"));
+
+        // Don't lint. The pattern expects `&str`
+        let s = String::from("x");
+        let _ = matches!(&*s, "x");
+    }
+}
+
+// The reborrowed reference is the result of a method chain
+mod method_chain {
+    fn f(_: &str) {}
+
+    fn main() {
+        let v = ["a", "b"];
+        let mut it = v.iter().copied();
+        let _: &str = &*it.next().unwrap();
+        f(&*it.next().unwrap());
+    }
+}
+
+// Only the operand comes from a macro
+mod mut_receivers {
+    use std::io::Read;
+
+    struct Foo;
+    trait Mutate {
+        fn mutate(&mut self) {}
+    }
+    impl Mutate for &Foo {}
+
+    fn get(x: &Foo) -> &Foo {
+        x
+    }
+
+    fn main() {
+        let x = &Foo;
+        let mut buf = [0u8; 4];
+        let s: &[u8] = &[1, 2, 3, 4];
+        let mut ms: &[u8] = &[1, 2, 3, 4];
+
+        // Don't lint. The method would borrow `x` mutably
+        (&*x).mutate();
+        (&*s).read_exact(&mut buf).unwrap();
+        // Don't lint. The method would advance `ms`
+        (&*ms).read_exact(&mut buf).unwrap();
+        ms.read_exact(&mut buf).unwrap();
+
+        // The receiver is a temporary either way
+        (&*get(x)).mutate();
+    }
+}
+
+mod macro_operands {
+    fn f(_: &str) {}
+    fn g(_: &u32) {}
+    fn get() -> &'static str {
+        ""
+    }
+
+    macro_rules! get {
+        () => {
+            get()
+        };
+    }
+    macro_rules! get_ref {
+        () => {
+            &0
+        };
+    }
+    macro_rules! deref {
+        ($e:expr) => {
+            *$e
+        };
+    }
+    macro_rules! addr_of {
+        ($e:expr) => {
+            &$e
+        };
+    }
+
+    fn main() {
+        f(&*get!());
+        g(&*get_ref!());
+
+        // Don't lint. Either the `&` or the `*` is part of the macro
+        let s = get();
+        f(&deref!(s));
+        f(addr_of!(*s));
+    }
+}
+
 with_span!(
     span
 
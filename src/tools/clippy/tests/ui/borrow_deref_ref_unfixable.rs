@@ -11,3 +11,28 @@ fn two_helps() {
         //~| NOTE: `-D clippy::borrow-deref-ref` implied by `-D warnings`
     }
 }
+
+// `map` closures over nested references
+mod iter_map {
+    fn main() {
+        let s = String::new();
+        let v: Vec<&String> = vec![&s];
+        let _: Vec<&String> = v.iter().map(|x| &**x).collect();
+        //~^ ERROR: deref on an immutable reference
+
+        let _: Vec<&String> = v.iter().map(|x: &&String| &**x).collect();
+        //~^ ERROR: deref on an immutable reference
+
+        // `copied()` would only remove one reference
+        let w: Vec<&&String> = v.iter().collect();
+        let _: Vec<&String> = w.iter().map(|x| &***x).collect();
+        // `*x` isn't a reference
+        let b: Vec<Box<u8>> = vec![Box::new(0)];
+        let _: Vec<&u8> = b.iter().map(|x| &**x).collect();
+
+        // not the whole closure body
+        let _: Vec<(&String, u8)> = v.iter().map(|x| (&**x, 0)).collect();
+        // not an iterator
+        let _: Option<&String> = v.first().map(|x| &**x);
+    }
+}
@@ -2,6 +2,7 @@
     unused,
     clippy::redundant_clone,
     clippy::deref_addrof,
+    clippy::explicit_auto_deref,
     clippy::no_effect,
     clippy::unnecessary_operation,
     clippy::vec_init_then_push,
@@ -1,5 +1,10 @@
 #![warn(clippy::comparison_to_empty)]
-#![allow(clippy::borrow_deref_ref, clippy::needless_if, clippy::useless_vec)]
+#![allow(
+    clippy::borrow_deref_ref,
+    clippy::explicit_auto_deref,
+    clippy::needless_if,
+    clippy::useless_vec
+)]
 #![feature(let_chains)]
 
 fn main() {
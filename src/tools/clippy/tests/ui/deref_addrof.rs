@@ -45,6 +45,20 @@ fn main() {
     let _ = unsafe { *core::ptr::addr_of!(a) };
 }
 
+fn place_contexts() {
+    let mut a = 10;
+    *&mut a = 5;
+    *&mut a += 1;
+    let _ = *&mut a;
+
+    let mut v = vec![1];
+    (*&mut v).push(2);
+    let _ = (*&v).len();
+    // `v` isn't moved by a wildcard pattern
+    let _ = *&v;
+    let _ = v.len();
+}
+
 #[derive(Copy, Clone)]
 pub struct S;
 #[inline_macros]
@@ -1,4 +1,4 @@
-#![feature(closure_lifetime_binder)]
+#![feature(closure_lifetime_binder, lazy_cell, try_blocks)]
 #![warn(clippy::explicit_auto_deref)]
 #![allow(
     dead_code,
@@ -328,6 +328,7 @@ struct S8 {
         let mut x = U {
             u: core::mem::ManuallyDrop::new(S8 { x: "" }),
         };
+        // Don't lint. Field access derefs through `ManuallyDrop`
         let _ = &mut (*x.u).x;
         let _ = &mut (*{ x.u }).x;
         let _ = &mut ({ *x.u }).x;
@@ -335,6 +336,7 @@ struct S8 {
         let mut x = U {
             u: Wrap(core::mem::ManuallyDrop::new(S8 { x: "" })),
         };
+        // Don't lint. Field access derefs through `ManuallyDrop`
         let _ = &mut (**x.u).x;
         let _ = &mut (**{ x.u }).x;
         let _ = &mut ({ **x.u }).x;
@@ -345,3 +347,949 @@ struct S8 {
         let _ = &mut ({ *x.u }).x;
     }
 }
+
+fn nested_ref_generic<'a, T: 'a>(items: impl Iterator<Item = &'a &'a &'a T>) {
+    fn takes_ref<U>(_: &U) {}
+    fn takes_ref_ref<U>(_: &&U) {}
+
+    for item in items {
+        takes_ref_ref(&**item);
+        // Don't lint. `U` would be inferred as `&&T`
+        takes_ref(&**item);
+    }
+}
+
+mod write_args {
+    use std::fmt::{self, Write};
+
+    struct S {
+        inner: Box<str>,
+        count: Box<usize>,
+        name: &'static str,
+        s: &'static String,
+    }
+    impl fmt::Display for S {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", &*self.inner)?;
+            write!(f, "{:?}", &*self.inner)?;
+            writeln!(f, "{count}", count = *self.count)?;
+            write!(f, "{} {0:?}", &*self.name)?;
+
+            // Don't lint. `String` derefs to `str` through a user defined `Deref` impl
+            write!(f, "{}", &**self.s)?;
+            // Don't lint. `Pointer` formats the address of the reference
+            write!(f, "{:p}", &*self.name)?;
+            write!(f, "{} {0:p}", &*self.name)?;
+            // Don't lint. Left to `deref_addrof`
+            #[allow(clippy::deref_addrof)]
+            write!(f, "{}", **&&self.name)?;
+            // Don't lint. Used as the width
+            write!(f, "{:1$}", "", *self.count)?;
+
+            let mut s = String::new();
+            write!(s, "{}", *self.count).unwrap();
+            let name = self.name;
+            write!(s, "{name}").unwrap();
+            Ok(())
+        }
+    }
+}
+
+fn full_slice(v: Vec<u8>) {
+    fn takes_slice(_: &[u8]) {}
+
+    // Don't lint. Full-range slicing is only linted when `explicit-auto-deref-full-slices` is set
+    takes_slice(&v[..]);
+}
+
+fn method_receivers(x: &String, y: &Vec<u8>) {
+    let _ = (&*x).trim();
+    let _ = (&**x).trim();
+    let _ = (&*y).first();
+    let _ = (&**y).first();
+
+    // Don't lint. `x.len()` would resolve to `String::len`
+    let _ = (&**x).len();
+    // Don't lint. `y.len()` would resolve to `Vec::len`
+    let _ = (&**y).len();
+}
+
+fn lifetime_extension() {
+    use std::ops::Deref;
+    use std::sync::RwLock;
+
+    struct PrintOnDrop(&'static str);
+    impl Drop for PrintOnDrop {
+        fn drop(&mut self) {
+            println!("dropped {}", self.0);
+        }
+    }
+    impl Deref for PrintOnDrop {
+        type Target = str;
+        fn deref(&self) -> &str {
+            self.0
+        }
+    }
+
+    // Don't lint. `&PrintOnDrop(..)` would only be dropped at the end of the block
+    let _: &str = &*PrintOnDrop("first");
+    let _: (&str, u32) = (&*PrintOnDrop("second"), 0);
+    let lock = RwLock::new(String::new());
+    let _: &str = &*lock.read().unwrap();
+    println!("end of block");
+
+    // Lint. The temporary isn't in an extending position
+    f_str(&*PrintOnDrop("third"));
+    // Lint. Dropping a `String` has no observable side effects
+    let _: &str = &*String::from("fifth");
+    let s = PrintOnDrop("fourth");
+    let _: &str = &*s;
+}
+
+#[allow(clippy::useless_vec)]
+fn repeat_elements<'a>(s: &'a String, r: &&String) -> Vec<&'a str> {
+    let _: Vec<&str> = vec![&**s; 3];
+    let _: [&str; 3] = [&**s; 3];
+    let _: [&String; 3] = [&**r; 3];
+
+    // Don't lint. The element type is inferred from the element
+    let _ = vec![&**s; 3];
+    let _ = [&**s; 3];
+    let _: Vec<_> = vec![&**s; 3];
+    let _: [_; 3] = [&**s; 3];
+
+    vec![&**s; 3]
+}
+
+fn manually_drop() {
+    use std::mem::ManuallyDrop;
+
+    struct S {
+        x: u32,
+    }
+    let md = ManuallyDrop::new(S { x: 0 });
+    let r = &md;
+    // Don't lint. Auto-deref goes through `ManuallyDrop`
+    let _: &S = &*md;
+    let _: &S = &**r;
+    let _ = (*md).x;
+    let _ = (**r).x;
+}
+
+fn try_block_tails() {
+    let s = String::new();
+    let b = Box::new(s.clone());
+    // Don't lint. The tail of a `try` block isn't a coercion site
+    let _: Option<&str> = try { &*s };
+    let _: Option<&String> = try { &*b };
+    let _: Result<&str, ()> = try { &**b };
+
+    // The block's own tail is still checked
+    let _: Option<&str> = try {
+        let x: &str = &*s;
+        x
+    };
+}
+
+fn as_ref_params() {
+    fn concrete(_: &str) {}
+    fn generic<S: AsRef<str>>(_: S) {}
+    fn generic_ref<S: AsRef<str> + ?Sized>(_: &S) {}
+    fn impl_trait(_: impl AsRef<str>) {}
+
+    let s = String::new();
+    concrete(&*s);
+    // Don't lint. Removing the deref changes the inferred type
+    generic(&*s);
+    generic_ref(&*s);
+    impl_trait(&*s);
+}
+
+mod generic_targets {
+    // The suggestions for generic targets are `MaybeIncorrect`
+    fn param<T>(x: &Box<T>) -> &T {
+        &**x
+    }
+    fn param_mut<T>(x: &mut Box<T>) -> &mut T {
+        &mut **x
+    }
+    fn adt<T>(x: &Box<Vec<T>>) -> &Vec<T> {
+        &**x
+    }
+    fn concrete(x: &Box<Vec<u32>>) -> &Vec<u32> {
+        &**x
+    }
+}
+
+#[allow(clippy::deref_addrof)]
+fn collapsed_chains() {
+    fn f(_: &str) {}
+
+    let s = String::new();
+    let x = &s;
+    // Each chain is reduced in a single suggestion
+    f(&*&*&*x);
+    f(&*&*&*s);
+    let _: &str = &*&*&*x;
+    let _: &String = &*&*&*x;
+    let _ = (&*&*&*x).len();
+
+    let mut s = String::new();
+    let y = &mut s;
+    let _: &mut str = &mut *&mut *&mut **y;
+}
+
+fn non_null() {
+    use std::ptr::NonNull;
+
+    let mut s = String::new();
+    let mut p = NonNull::from(&mut s);
+    unsafe {
+        let _: &str = &**p.as_ref();
+        let _: &mut str = &mut **p.as_mut();
+        // Only the raw pointer deref is kept
+        let _: &str = &**p.as_ptr();
+    }
+    // The suggestion stays inside the `unsafe` block
+    let _: &str = unsafe { &**p.as_ref() };
+}
+
+fn lazy_lock() {
+    use std::sync::LazyLock;
+
+    static LAZY: LazyLock<String> = LazyLock::new(String::new);
+    fn f(_: &str) {}
+
+    f(&*LAZY);
+    f(&**LAZY);
+    let _: &String = &*LAZY;
+    let _: &str = &**LAZY;
+}
+
+fn mut_boxed_box() {
+    fn f(_: &mut i32) {}
+
+    let mut b: Box<Box<i32>> = Box::new(Box::new(0));
+    f(&mut **b);
+    let _: &mut i32 = &mut **b;
+    let _: &mut Box<i32> = &mut *b;
+
+    let r = &mut b;
+    f(&mut ***r);
+    let _: &mut i32 = &mut ***r;
+    let _: &mut Box<i32> = &mut **r;
+    // `r` is reborrowed by the coercion
+    f(&mut ***r);
+}
+
+fn slice_bindings() {
+    let v: Vec<&str> = vec!["a"];
+    let r = &v;
+    let _: &[&str] = &**r;
+    let _: &[&_] = &**r;
+
+    let b: Vec<Box<i32>> = vec![Box::new(0)];
+    let r = &b;
+    let _: &[Box<i32>] = &**r;
+    let _: &[Box<_>] = &**r;
+
+    let a: Box<[u8; 2]> = Box::new([0, 1]);
+    let r = &a;
+    let _: &[u8; 2] = &**r;
+    // Don't lint. The array is unsized after the deref, which auto-deref won't do.
+    let _: &[u8] = &**r;
+}
+
+mod slice_returns {
+    struct S<T> {
+        v: Vec<T>,
+        s: String,
+        b: Box<[T]>,
+    }
+    impl<T> S<T> {
+        fn slice(&self) -> &[T] {
+            &*self.v
+        }
+        fn slice_mut(&mut self) -> &mut [T] {
+            &mut *self.v
+        }
+        fn boxed_slice(&self) -> &[T] {
+            &*self.b
+        }
+        fn str(&self) -> &str {
+            &*self.s
+        }
+        fn branches(&self) -> &[T] {
+            if self.v.is_empty() { &[] } else { &*self.v }
+        }
+        fn explicit_return(&self) -> &str {
+            return &*self.s;
+        }
+    }
+
+    trait Items {
+        type Item;
+        fn items(&self) -> &[Self::Item];
+    }
+    impl<T> Items for S<T> {
+        type Item = T;
+        fn items(&self) -> &[T] {
+            &*self.v
+        }
+    }
+}
+
+// Any deref written in a constant has already been checked by the compiler, auto-deref performs
+// the same derefs.
+mod const_initializers {
+    use std::fmt::Display;
+
+    const S: &&str = &"x";
+    const R: &&String = &&String::new();
+
+    const X: &str = &**S;
+    static Y: &str = &**S;
+    const Z: &String = &**R;
+    // Don't lint. The type isn't deref stable
+    const D: &dyn Display = &*S;
+
+    struct T;
+    impl T {
+        const A: &'static str = &**S;
+    }
+    trait Tr {
+        const B: &'static str = &**S;
+    }
+}
+
+// `weak.upgrade().map(|r| &*r)` would return a borrow of the closure's argument, the upgraded
+// value has to be borrowed first.
+#[allow(clippy::option_as_ref_deref)]
+fn upgraded_weak() {
+    use std::rc::{Rc, Weak};
+
+    fn f(_: Option<&String>) {}
+    fn g(_: Option<&str>) {}
+
+    let rc = Rc::new(String::new());
+    let weak: Weak<String> = Rc::downgrade(&rc);
+    let up = weak.upgrade();
+    f(up.as_ref().map(|r| -> &String { &**r }));
+    g(up.as_ref().map(|r| -> &str { &**r }));
+
+    // Don't lint. The closure's return type is inferred
+    f(up.as_ref().map(|r| &**r));
+    let _: Option<&String> = up.as_ref().map(|r| &**r);
+}
+
+fn tuple_elements() {
+    fn f(_: (&str, &str)) {}
+
+    let a = String::new();
+    let b = String::new();
+    let _: (&str, &str) = (&*a, &*b);
+    let _: (&str, u32) = (&*a, 0);
+    let _: ((&str, &str), &str) = ((&*a, &*b), &*a);
+    let _: (&str,) = (&*a,);
+    f((&*a, &*b));
+
+    // Don't lint. The tuple's type is inferred from the elements
+    let _ = (&*a, &*b);
+    let _: (&_, u32) = (&*a, 0);
+    let _: (_, u32) = (&*a, 0);
+}
+
+fn iterator_items() {
+    fn f(_: &str) {}
+
+    let v = ["a", "b"];
+    let mut it = v.iter();
+    let _: &str = &**it.next().unwrap();
+    f(&**it.next().unwrap());
+
+    let s = [String::new()];
+    let mut it = s.iter();
+    f(&**it.next().unwrap());
+    // Don't lint. Auto-deref doesn't apply to the reborrow
+    f(&*it.next().unwrap());
+}
+
+mod mut_field_returns {
+    #[allow(clippy::box_collection, clippy::redundant_allocation)]
+    struct S<'a, T> {
+        b: Box<T>,
+        r: &'a mut String,
+        bb: Box<Box<T>>,
+        s: Box<String>,
+    }
+    impl<T> S<'_, T> {
+        fn boxed(&mut self) -> &mut T {
+            &mut *self.b
+        }
+        fn reference(&mut self) -> &mut str {
+            &mut **self.r
+        }
+        fn boxed_box(&mut self) -> &mut T {
+            &mut **self.bb
+        }
+        fn inner_box(&mut self) -> &mut Box<T> {
+            &mut *self.bb
+        }
+        fn str(&mut self) -> &mut str {
+            &mut **self.s
+        }
+    }
+
+    trait Get<T> {
+        fn get(&mut self) -> &mut T;
+    }
+    impl<T> Get<T> for S<'_, T> {
+        fn get(&mut self) -> &mut T {
+            &mut *self.b
+        }
+    }
+}
+
+fn interior_mutability() {
+    use std::cell::{Cell, RefCell, UnsafeCell};
+
+    fn f(_: &str) {}
+    fn g(_: &Cell<i32>) {}
+    fn h(_: &UnsafeCell<i32>) {}
+
+    // Don't lint. The cell's contents are accessed explicitly
+    let c = RefCell::new(String::new());
+    let b = c.borrow();
+    f(&*b);
+    f(&**b);
+    f(&*c.borrow());
+    let _: &String = &*c.borrow();
+    let mut m = c.borrow_mut();
+    let _: &mut String = &mut *m;
+    let x = Box::new(Cell::new(0));
+    g(&*x);
+    let u = Box::new(UnsafeCell::new(0));
+    h(&*u);
+    drop(b);
+
+    let s = Box::new(String::from("a"));
+    f(&**s);
+}
+
+mod macro_operands {
+    fn f(_: &str) {}
+
+    macro_rules! boxed {
+        () => {
+            Box::new(String::from("a"))
+        };
+    }
+    macro_rules! deref {
+        ($e:expr) => {
+            *$e
+        };
+    }
+    macro_rules! addr_of {
+        ($e:expr) => {
+            &$e
+        };
+    }
+
+    fn main() {
+        let b = boxed!();
+        let r = &b;
+        f(&*boxed!());
+        f(&*deref!(r));
+        f(&**deref!(&r));
+
+        let x = 1;
+        f(&*format!("{}", x));
+        f(&*format!("{x}"));
+        let _: &str = &*format!("{x}");
+
+        // Don't lint. Either the `&` or the `*` is part of the macro
+        f(&deref!(b));
+        f(addr_of!(*b));
+    }
+}
+
+fn compound_assign() {
+    use std::ops::AddAssign;
+
+    struct V(i32);
+    impl AddAssign<&V> for V {
+        fn add_assign(&mut self, other: &V) {
+            self.0 += other.0;
+        }
+    }
+    struct W(i32);
+    impl AddAssign for W {
+        fn add_assign(&mut self, other: W) {
+            self.0 += other.0;
+        }
+    }
+
+    let mut v = V(0);
+    let b = Box::new(V(1));
+    v += &*b;
+    let r = &&V(1);
+    v += &**r;
+
+    let mut s = String::new();
+    let t = String::from("a");
+    s += &*t;
+
+    // Don't lint. The operand's type isn't determined by a single impl
+    let mut x = 0;
+    let b = Box::new(1);
+    x += &*b;
+    x += *b;
+    let mut w = W(0);
+    w += *Box::new(W(1));
+}
+
+fn compound_assign_generic<T: for<'a> std::ops::AddAssign<&'a T>>(mut x: T, y: &Box<T>) {
+    // Don't lint. The operand's type comes from the bounds
+    x += &**y;
+}
+
+mod async_bodies {
+    use std::fmt::Display;
+
+    async fn tail(x: &String) -> &str {
+        &**x
+    }
+
+    async fn early_return(x: &Box<String>) -> &String {
+        if x.is_empty() {
+            return &**x;
+        }
+        &**x
+    }
+
+    async fn generic<'a, T>(x: &'a &'a T) -> &'a T {
+        &**x
+    }
+
+    // Don't lint. The returned type changes
+    async fn opaque(x: &String) -> impl Display + '_ {
+        &**x
+    }
+    async fn trait_object<'a>(x: &'a &'a String) -> &'a dyn Display {
+        &**x
+    }
+}
+
+// Returned values are linted the same whether they're returned explicitly or from the tail
+mod return_positions {
+    use std::fmt::Display;
+
+    macro_rules! id {
+        ($e:expr) => {
+            $e
+        };
+    }
+    macro_rules! ret {
+        ($e:expr) => {
+            return $e
+        };
+    }
+
+    fn tail(x: &String) -> &str {
+        &**x
+    }
+    fn explicit(x: &String) -> &str {
+        return &**x;
+    }
+
+    fn tail_generic<'a, T: Display>(x: &'a &'a T) -> &'a T {
+        &**x
+    }
+    fn explicit_generic<'a, T: Display>(x: &'a &'a T) -> &'a T {
+        return &**x;
+    }
+
+    fn tail_macro(x: &String) -> &str {
+        id!(&**x)
+    }
+
+    // Don't lint. The `return` is written by the macro
+    fn explicit_macro(x: &String) -> &str {
+        ret!(&**x)
+    }
+
+    // Don't lint. The returned type changes
+    fn tail_opaque<'a>(x: &'a &'a String) -> impl Display + 'a {
+        &**x
+    }
+    fn explicit_opaque<'a>(x: &'a &'a String) -> impl Display + 'a {
+        return &**x;
+    }
+}
+
+fn path_and_os_str() {
+    use std::ffi::{OsStr, OsString};
+    use std::path::{Path, PathBuf};
+
+    fn path(_: &Path) {}
+    fn os_str(_: &OsStr) {}
+
+    let p = PathBuf::new();
+    let o = OsString::new();
+    path(&*p);
+    os_str(&*o);
+    let _: &Path = &*p;
+    let _: &OsStr = &*o;
+
+    let r = &p;
+    path(&**r);
+    let _: &Path = &**r;
+
+    // Don't lint. The borrowed type would change
+    let _ = &*p;
+    fn generic<T: AsRef<Path>>(_: T) {}
+    generic(&*p);
+}
+
+fn array_unsize_args() {
+    fn slice(_: &[u8]) {}
+    fn mut_slice(_: &mut [u8]) {}
+    fn array(_: &[u8; 4]) {}
+    fn generic<T: AsRef<[u8]> + ?Sized>(_: &T) {}
+
+    let mut b = Box::new([0u8; 4]);
+    let bb = Box::new(Box::new([0u8; 4]));
+    array(&*b);
+    array(&**bb);
+
+    // Don't lint. The array is unsized after the deref, which auto-deref won't do
+    slice(&*b);
+    slice(&**bb);
+    mut_slice(&mut *b);
+    let f = |_: &[u8]| {};
+    f(&*b);
+    // Don't lint. The parameter's type would change from the array to the box
+    generic(&*b);
+}
+
+fn index_bases(x: &mut Vec<u32>, b: &mut Box<[u32]>, s: &String, a: &[u32; 2]) {
+    use std::collections::HashMap;
+    use std::ops::{Deref, Index};
+
+    let _ = (&*x)[0];
+    let _ = (&mut *x)[0];
+    (&mut *x)[0] = 1;
+    (&mut *x)[0] += 1;
+    (*x)[0] = 1;
+    let _ = &mut (&mut *x)[1..];
+    let _ = (&**b)[0];
+    (&mut **b)[0] = 1;
+    (&mut *b)[0] = 1;
+    let _ = (*a)[0];
+    let m: &HashMap<u32, u32> = &HashMap::new();
+    let _ = (*m)[&0];
+
+    // Don't lint. `String` is indexed by ranges itself
+    let _ = (&**s)[1..];
+    let _ = (**s)[1..];
+
+    // Don't lint. The wrapper has its own `Index` impl
+    struct W(Vec<u32>);
+    impl Deref for W {
+        type Target = Vec<u32>;
+        fn deref(&self) -> &Vec<u32> {
+            &self.0
+        }
+    }
+    impl Index<usize> for W {
+        type Output = u32;
+        fn index(&self, _: usize) -> &u32 {
+            &0
+        }
+    }
+    let w = &W(Vec::new());
+    let _ = (**w)[0];
+
+    // Don't lint. Raw pointers aren't auto-derefed
+    let p: *const [u32; 2] = a;
+    let _ = unsafe { (*p)[0] };
+}
+
+mod trait_object_casts {
+    use std::fmt::Display;
+
+    #[allow(clippy::borrowed_box, clippy::boxed_local)]
+    fn f(bd: Box<dyn Display>, b: Box<u32>, rr: &&u32, rb: &Box<u32>, rd: &&dyn Display) {
+        // Don't lint. The cast unsizes the type it's given rather than auto-dereferencing it
+        let _ = &*bd as &dyn Display;
+        let _ = &*b as &dyn Display;
+        let _ = &**rr as &dyn Display;
+        let _ = &**rb as &dyn Display;
+        let _ = &**rd as &dyn Display;
+        let _ = &*b as *const dyn Display;
+    }
+}
+
+#[allow(
+    clippy::borrowed_box,
+    clippy::boxed_local,
+    clippy::needless_borrowed_reference,
+    clippy::never_loop
+)]
+fn scrutinees(
+    rr: &&Option<String>,
+    rm: &mut &mut Option<String>,
+    m: &mut Option<String>,
+    rs: &mut &Option<String>,
+    b: Box<Option<String>>,
+    rb: &Box<Option<String>>,
+) {
+    if let Some(v) = &**rr {}
+    while let Some(v) = &**rr {
+        break;
+    }
+    let Some(v) = &**rr else { return };
+    match &**rr {
+        Some(v) => {},
+        None => {},
+    }
+    match &**rr {
+        Some(_) | None => {},
+    }
+    if let Some(v) = &mut **rm {
+        v.push('a');
+    }
+    if let Some(v) = &mut *m {
+        v.push('a');
+    }
+    if let Some(v) = &**rs {}
+
+    // Don't lint. Match ergonomics don't see through a `Box`
+    if let Some(v) = &*b {}
+    if let Some(v) = &**rb {}
+    // Don't lint. The bindings would borrow mutably
+    if let Some(v) = &**rm {}
+    // Don't lint. The patterns don't peel off references
+    if let &Some(ref v) = &**rr {}
+    let x = &**rr;
+}
+
+#[allow(clippy::borrowed_box, clippy::boxed_local)]
+fn block_tails(x: &String, rr: &&String, rm: &mut &mut String, b: Box<str>) {
+    // Only the tail expression is replaced
+    let _: &str = { &**rr };
+    let _: &str = { &**x };
+    let _: &str = { &*b };
+    let _: &str = {
+        let _ = 0;
+        &**rr
+    };
+    let _: &str = unsafe { &**rr };
+    let _: &mut str = { &mut ***rm };
+    rm.push('a');
+    f_str({ &**x });
+}
+
+fn shared_interior_mutability() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex, RwLock};
+
+    fn f(_: &str) {}
+    fn g(_: &RefCell<String>) {}
+
+    // Don't lint. The guards are dereferenced explicitly
+    let rc = Rc::new(RefCell::new(String::new()));
+    let rrc = &rc;
+    f(&**rc.borrow());
+    f(&**rrc.borrow_mut());
+    let _: &str = &**rc.borrow();
+    let m = Arc::new(Mutex::new(String::new()));
+    f(&**m.lock().unwrap());
+    let guard = m.lock().unwrap();
+    f(&**guard);
+    drop(guard);
+    let l = Arc::new(RwLock::new(String::new()));
+    f(&**l.read().unwrap());
+    f(&**l.write().unwrap());
+    // Don't lint. The derefs reach the cell
+    g(&*rc);
+    g(&**rrc);
+}
+
+#[allow(clippy::box_collection)]
+mod try_chains {
+    struct S {
+        s: String,
+        b: Box<String>,
+    }
+    impl S {
+        fn foo(&self) -> Option<&Self> {
+            Some(self)
+        }
+        fn res(&self) -> Result<&Self, ()> {
+            Ok(self)
+        }
+        fn bar(&self) -> &String {
+            &self.s
+        }
+        fn boxed(&self) -> &Box<String> {
+            &self.b
+        }
+    }
+
+    fn f_str(_: &str) {}
+
+    fn option(x: &S) -> Option<()> {
+        f_str(&**x.foo()?.bar());
+        f_str(&***x.foo()?.boxed());
+        let _: &str = &**x.foo()?.bar();
+        // Don't lint. The reborrow is handled by `borrow_deref_ref`
+        let _: &String = &*x.foo()?.bar();
+        Some(())
+    }
+
+    fn result(x: &S) -> Result<(), ()> {
+        f_str(&**x.res()?.bar());
+        f_str(&**x.res()?.foo().ok_or(())?.bar());
+        Ok(())
+    }
+
+    // Don't lint. `?` isn't implemented for references, so every deref of the operand is needed.
+    // e.g. `(&*res)?` doesn't compile
+    fn operands(res: &Result<u32, ()>, rr: &&Result<u32, ()>, b: &Box<Result<u32, ()>>) -> Result<(), ()> {
+        let _ = (*res)?;
+        let _ = (**rr)?;
+        let _ = (**b)?;
+        Ok(())
+    }
+
+    fn option_operands(o: &Option<u32>, oo: &&Option<u32>) -> Option<()> {
+        let _ = (*o)?;
+        let _ = (**oo)?;
+        Some(())
+    }
+}
+
+fn collect_targets(v: &[String]) {
+    // Don't lint. The closure's return type is only inferred from the `collect` target
+    let _ = v.iter().map(|s: &String| &**s).collect::<Vec<&str>>();
+    let _ = v.iter().map(|s| &**s).collect::<Vec<&str>>();
+    let _: Vec<&str> = v.iter().map(|s: &String| &**s).collect();
+    let _ = v.iter().map(|s: &String| &*s).collect::<Vec<&String>>();
+
+    // The return type is written on the closure
+    let _ = v.iter().map(|s: &String| -> &str { &**s }).collect::<Vec<_>>();
+    let _: Vec<&str> = v.iter().map(|s| -> &str { &**s }).collect();
+}
+
+fn unsafe_blocks(x: &String, p: *const String) {
+    unsafe fn get(x: &String) -> &String {
+        x
+    }
+    fn f_str(_: &str) {}
+
+    // The `unsafe` block is kept whole
+    f_str(&**unsafe { get(x) });
+    let _: &str = &**unsafe { &*p };
+    let _: &str = &**unsafe {
+        let _ = 0;
+        get(x)
+    };
+
+    // The suggestion stays inside the `unsafe` block
+    unsafe { f_str(&**get(x)) };
+    f_str(unsafe { &**get(x) });
+    unsafe {
+        let _: &str = &**get(x);
+        f_str(&**x);
+    }
+    // Only the reference deref is removed
+    let _: &str = unsafe { &**p };
+}
+
+fn struct_updates(x: &String) {
+    #[derive(Clone, Copy)]
+    struct S<'a> {
+        r: &'a str,
+        n: i32,
+    }
+    #[derive(Clone, Copy)]
+    struct G<T> {
+        t: T,
+        n: i32,
+    }
+    fn default<'a>() -> S<'a> {
+        S { r: "", n: 0 }
+    }
+
+    let base = default();
+    let _ = S { r: &**x, ..default() };
+    let _ = S { r: &**x, ..base };
+    let rb = &base;
+    let _ = S { r: &**x, ..*rb };
+
+    // Don't lint. The field's type comes from the base
+    let g = G { t: "", n: 0 };
+    let _ = G { t: &**x, ..g };
+}
+
+#[allow(clippy::box_collection)]
+fn mut_vec_slices(mut vec: Vec<u8>, v: &mut Vec<u8>, b: &mut Box<Vec<u8>>) {
+    fn f(_: &mut [u8]) {}
+
+    f(&mut *vec);
+    f(&mut **v);
+    f(&mut ***b);
+    let _: &mut [u8] = &mut *vec;
+    let _: &mut [u8] = &mut ***b;
+    // The coercion reborrows `v`
+    let s: &mut [u8] = &mut **v;
+    s[0] = 1;
+    f(&mut **v);
+    v.push(1);
+}
+
+fn track_caller_args(x: &String) {
+    use std::panic::Location;
+
+    #[track_caller]
+    fn f(_: &str) -> &'static Location<'static> {
+        Location::caller()
+    }
+    #[track_caller]
+    fn generic<T: ?Sized>(_: &T) -> &'static Location<'static> {
+        Location::caller()
+    }
+    struct S;
+    impl S {
+        #[track_caller]
+        fn m(&self, _: &str) -> &'static Location<'static> {
+            Location::caller()
+        }
+    }
+
+    // The call itself isn't moved, so the caller's location stays the same
+    let _ = f(&**x);
+    let _ = S.m(&**x);
+    // Don't lint. `generic` would be called with `String`
+    let _ = generic(&**x);
+}
+
+fn comparisons(a: &&str, b: &&str, s: &&&String, t: &&&String, x: &&i32, y: &&i32) {
+    let _ = **a == **b;
+    let _ = **a != **b;
+    let _ = &**a == &**b;
+    let _ = ***s == ***t;
+    let _ = **x == **y;
+
+    // Don't lint. A single deref is the usual way to compare the values
+    let _ = *a == *b;
+    // `op_ref` removes the borrows
+    let _ = &*a == &*b;
+    // Don't lint. The operands have different types
+    let _ = **a == ***s;
+    let _ = **a == *"";
+    // Don't lint. Ordering comparisons aren't linted
+    let _ = **x < **y;
+}
@@ -1,3 +1,4 @@
+#![feature(lazy_cell)]
 #![warn(clippy::explicit_deref_methods)]
 #![allow(unused_variables, unused_must_use)]
 #![allow(
@@ -7,6 +8,7 @@
     clippy::explicit_auto_deref,
     clippy::needless_borrow,
     clippy::no_effect,
+    clippy::redundant_reborrow,
     clippy::uninlined_format_args,
     clippy::unnecessary_literal_unwrap
 )]
@@ -128,3 +130,311 @@ pub fn deref_mut(self) -> u32 {
     let b = no_lint.deref();
     let b = no_lint.deref_mut();
 }
+
+fn parenthesized_receiver() {
+    let a: &mut String = &mut String::from("foo");
+    let x = String::from("bar");
+
+    // the parentheses are redundant and should be removed
+    let b: &str = (x).deref();
+    let b: &str = (*a).deref();
+    let b = (a).deref_mut();
+
+    // the parentheses are required here, and shouldn't be doubled
+    let b = (&x as &String).deref();
+    let b: &str = (x.clone() + "").deref();
+}
+
+fn pinned() {
+    use std::pin::Pin;
+
+    let mut x = String::new();
+    let _: &mut String = Pin::new(&mut x).deref_mut();
+    let mut p = Pin::new(&mut x);
+    let _: &String = p.deref();
+    let _: &mut String = p.deref_mut();
+}
+
+fn compound_assign() {
+    struct Counter(i32);
+    impl Deref for Counter {
+        type Target = i32;
+        fn deref(&self) -> &i32 {
+            &self.0
+        }
+    }
+    impl DerefMut for Counter {
+        fn deref_mut(&mut self) -> &mut i32 {
+            &mut self.0
+        }
+    }
+
+    let mut x = Box::new(0);
+    *x.deref_mut() += 1;
+    let y = &mut x;
+    *y.deref_mut() -= 1;
+    let mut c = Counter(0);
+    *c.deref_mut() *= 2;
+}
+
+fn cow_receivers() {
+    use std::borrow::Cow;
+
+    let mut cow: Cow<'_, str> = Cow::Borrowed("a");
+    let _: &str = cow.deref();
+    // `Cow` doesn't implement `DerefMut`, these only reach the outer pointer
+    let mut c = &mut cow;
+    let _: &mut Cow<'_, str> = c.deref_mut();
+    let mut b: Box<Cow<'_, str>> = Box::new(Cow::Borrowed("a"));
+    let _: &mut Cow<'_, str> = b.deref_mut();
+    // Mutable access goes through `to_mut`
+    cow.to_mut().push('b');
+}
+
+fn manually_drop() {
+    use std::mem::ManuallyDrop;
+
+    let mut md = ManuallyDrop::new(String::new());
+    let _: &String = md.deref();
+    let _: &mut String = md.deref_mut();
+}
+
+fn trait_objects() {
+    let x: &dyn Deref<Target = str> = &String::from("a");
+    let _: &str = x.deref();
+    let b: Box<dyn Deref<Target = str>> = Box::new(String::from("a"));
+    // `Box` is dereferenced first, the result is coerced through the trait object
+    let _: &str = b.deref();
+    let _: &dyn Deref<Target = str> = b.deref();
+    let _: &str = b.deref().deref();
+
+    let mut s = Box::new(String::from("a"));
+    let m: &mut dyn DerefMut<Target = String> = &mut s;
+    let _: &mut String = m.deref_mut();
+    let _: &String = m.deref().deref();
+}
+
+fn lazy_lock() {
+    use std::sync::LazyLock;
+
+    static LAZY: LazyLock<String> = LazyLock::new(String::new);
+    let _: &String = LAZY.deref();
+    let _: &str = LAZY.deref().deref();
+    let r = &LAZY;
+    let _: &String = r.deref();
+    let _: &str = r.deref().deref();
+}
+
+mod aliased_traits {
+    use core::ops::{Deref as _, DerefMut as _};
+
+    fn f(x: &mut String) {
+        let _: &str = x.deref();
+        let _: &mut str = x.deref_mut();
+    }
+}
+
+fn coerced_let_init() {
+    let s = String::from("a");
+    let x: &String = &s;
+    let mut m = String::from("b");
+    let y: &mut String = &mut m;
+
+    // the receiver coerces to the declared type
+    let _: &str = x.deref();
+    let _: &mut str = y.deref_mut();
+
+    // no declared type, or the declared type is inferred
+    let _ = x.deref();
+    let _: &_ = x.deref();
+    println!("{}", x.deref());
+    let _ = concat(x.deref());
+
+    // the receiver isn't a reference
+    let _: &str = s.deref();
+}
+
+fn slice_methods() {
+    fn slice(_: &[u8]) {}
+    fn generic<T: ?Sized>(_: &T) {}
+
+    let mut v = vec![1u8];
+    let _ = v.deref();
+    let _ = v.deref_mut();
+    generic(v.deref());
+    let r = &mut v;
+    let _ = r.deref_mut();
+
+    let mut s = String::new();
+    let _ = s.deref();
+    let _ = s.deref_mut();
+    generic(s.deref());
+
+    // coercion sites keep using `&*`
+    slice(v.deref());
+    let _: &[u8] = v.deref();
+    let _: &str = s.deref();
+
+    // the temporary would be dropped while borrowed
+    let _ = vec![1u8].deref();
+    let _ = String::new().deref();
+
+    // other containers keep using `&*`
+    let b: Box<[u8]> = Box::new([1]);
+    let _ = b.deref();
+}
+
+#[clippy::msrv = "1.6"]
+fn msrv_1_6() {
+    let mut v = vec![1u8];
+    let _ = v.deref();
+    let _ = v.deref_mut();
+    let s = String::new();
+    let _ = s.deref();
+}
+
+#[clippy::msrv = "1.7"]
+fn msrv_1_7() {
+    let mut v = vec![1u8];
+    let _ = v.deref();
+    let _ = v.deref_mut();
+    let s = String::new();
+    let _ = s.deref();
+}
+
+#[allow(clippy::borrowed_box)]
+fn pointer_receivers() {
+    let b: Box<String> = Box::default();
+    let rb: &Box<String> = &b;
+    let _ = rb.deref();
+    let _: &str = rb.deref().deref();
+
+    let s = String::new();
+    let rr: &&String = &&s;
+    // `<&String as Deref>::deref` only removes the outer reference
+    let _ = rr.deref();
+    let _: &str = rr.deref().deref();
+
+    let mut bb: Box<Box<i32>> = Box::new(Box::new(0));
+    let _ = bb.deref();
+    let _ = bb.deref().deref();
+    let _ = bb.deref_mut();
+    let _ = bb.deref_mut().deref_mut();
+}
+
+fn ufcs_operands() {
+    let b: Box<String> = Box::default();
+    // Only the method call is replaced. The path keeps determining the type of `Self`
+    let _ = <String as Deref>::deref(b.deref());
+    let _ = <_ as Deref>::deref(b.deref());
+    let _ = Deref::deref(b.deref());
+
+    // Don't lint. Calls through a path are left as written
+    let _ = (<Box<String> as Deref>::deref(&b)).deref();
+    let _: &str = <String as Deref>::deref(&Default::default());
+}
+
+fn path_and_os_str_methods() {
+    use std::ffi::{OsStr, OsString};
+    use std::path::{Path, PathBuf};
+
+    fn path(_: &Path) {}
+
+    let mut p = PathBuf::new();
+    let _ = p.deref();
+    let mut o = OsString::new();
+    let _ = o.deref();
+
+    // coercion sites keep using `&*`
+    path(p.deref());
+    let _: &OsStr = o.deref();
+
+    // there's no method borrowing them mutably
+    let _ = p.deref_mut();
+    let _ = o.deref_mut();
+}
+
+fn zero_sized_targets() {
+    struct Empty;
+    struct ToEmpty;
+    impl Deref for ToEmpty {
+        type Target = Empty;
+        fn deref(&self) -> &Empty {
+            &Empty
+        }
+    }
+
+    // `Aaa` derefs to `()`
+    let mut aaa = Aaa;
+    let _ = aaa.deref();
+    let _ = aaa.deref_mut();
+    let r = &aaa;
+    let _ = r.deref();
+    let _: &() = r.deref();
+    let m = &mut aaa;
+    let _ = m.deref_mut();
+
+    let e = ToEmpty;
+    let _ = e.deref();
+    let r = &e;
+    let _ = r.deref();
+    let _: &Empty = r.deref();
+}
+
+fn unsafe_blocks(x: &String, p: *const String) {
+    unsafe fn get(x: &String) -> &String {
+        x
+    }
+
+    // The `unsafe` block is kept whole
+    let _: &str = unsafe { get(x) }.deref();
+    let _: &str = unsafe { &*p }.deref();
+    let _ = unsafe { get(x) }.deref();
+
+    // The suggestion stays inside the `unsafe` block
+    let _: &str = unsafe { get(x).deref() };
+    let _ = unsafe { (*p).deref() };
+}
+
+fn user_newtypes() {
+    struct Name(String);
+    impl Deref for Name {
+        type Target = str;
+        fn deref(&self) -> &str {
+            &self.0
+        }
+    }
+    impl DerefMut for Name {
+        fn deref_mut(&mut self) -> &mut str {
+            &mut self.0
+        }
+    }
+    struct Outer(Name);
+    impl Deref for Outer {
+        type Target = Name;
+        fn deref(&self) -> &Name {
+            &self.0
+        }
+    }
+
+    let mut n = Name(String::new());
+    let _ = n.deref();
+    let _: &str = n.deref();
+    let _ = n.deref_mut();
+    let _: &mut str = n.deref_mut();
+
+    let rn = &n;
+    let _ = rn.deref();
+    let _: &str = rn.deref();
+    let rrn = &rn;
+    // `<&Name as Deref>::deref` only removes the outer reference
+    let _ = rrn.deref();
+
+    let o = Outer(Name(String::new()));
+    let _ = o.deref();
+    let _ = o.deref().deref();
+    let _: &str = o.deref().deref();
+    let _ = (*o).deref();
+    let ro = &o;
+    let _ = ro.deref().deref();
+}
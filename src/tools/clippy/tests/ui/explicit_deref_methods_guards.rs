@@ -0,0 +1,39 @@
+#![warn(clippy::needless_borrow, clippy::explicit_auto_deref, clippy::explicit_deref_methods)]
+
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+
+fn f(_: &String) {}
+fn f_str(_: &str) {}
+fn f_mut(_: &mut String) {}
+
+fn main() {
+    let cell = RefCell::new(String::new());
+
+    // The `deref` calls are replaced with `&*`, the guard stays where it is
+    let _: &String = cell.borrow().deref();
+    let _ = cell.borrow().deref();
+    f(cell.borrow().deref());
+    let _ = cell.borrow_mut().deref_mut();
+    let mut guard = cell.borrow_mut();
+    let _: &mut String = guard.deref_mut();
+    drop(guard);
+
+    // Don't lint. The guard's contents are borrowed explicitly
+    f(&*cell.borrow());
+    f_str(&*cell.borrow());
+    f(&&*cell.borrow());
+    f_str(&&*cell.borrow());
+    f_mut(&mut *cell.borrow_mut());
+    f_mut(&mut &mut *cell.borrow_mut());
+    let _: &String = &*cell.borrow();
+    let _: &str = &&*cell.borrow();
+    let _ = (&*cell.borrow()).len();
+    let _ = (&&*cell.borrow()).len();
+
+    let guard = cell.borrow();
+    let _: &String = &&*guard;
+    f(&&*guard);
+    let x = &*guard;
+    let _ = x.len();
+}
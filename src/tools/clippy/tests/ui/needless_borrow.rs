@@ -1,6 +1,9 @@
+//@aux-build:proc_macro_derive.rs
 #![feature(lint_reasons)]
 #![allow(
     unused,
+    clippy::explicit_auto_deref,
+    clippy::redundant_reborrow,
     clippy::uninlined_format_args,
     clippy::unnecessary_mut_passed,
     clippy::unnecessary_to_owned,
@@ -217,6 +220,7 @@ struct Foo {
         let mut x = U {
             u: ManuallyDrop::new(Foo { x: 0 }),
         };
+        // Don't lint. Field access derefs through `ManuallyDrop`
         let _ = &mut (&mut x.u).x;
         let _ = &mut (&mut { x.u }).x;
         let _ = &mut ({ &mut x.u }).x;
@@ -224,6 +228,7 @@ struct Foo {
         let mut x = U {
             u: Wrap(ManuallyDrop::new(Foo { x: 0 })),
         };
+        // Don't lint. Field access derefs through `ManuallyDrop`
         let _ = &mut (&mut x.u).x;
         let _ = &mut (&mut { x.u }).x;
         let _ = &mut ({ &mut x.u }).x;
@@ -247,3 +252,311 @@ fn f() {
         (&S).f::<()>();
     }
 }
+
+fn non_copy_local_used_later() {
+    struct S {
+        x: u32,
+    }
+    fn takes_mut(_: &mut String) {}
+    fn takes_ref(_: &String) {}
+
+    // `s` is borrowed again by the method call, so it isn't moved
+    let s = String::new();
+    let _ = (&s).len();
+    let _ = s.len();
+
+    let t = S { x: 0 };
+    let _ = (&t).x;
+    let _ = t.x;
+
+    // `y` is reborrowed at the coercion site
+    let mut x = String::new();
+    let mut y = &mut x;
+    takes_mut(&mut y);
+    takes_mut(y);
+
+    // Only the outer borrow is removed, so `w` is still borrowed
+    let w = String::new();
+    takes_ref(&&w);
+    let _ = w.len();
+}
+
+fn keeps_mut_reborrow<T>(x: &mut Option<&mut T>, y: &mut T) {
+    let mut x = x.as_mut().unwrap();
+    let _ = match 0 {
+        0 => y,
+        _ => &mut x,
+    };
+}
+
+fn included_expression() {
+    let a = 5;
+    // Don't lint. Changes to the included file would affect every place including it
+    let _ = x(include!("auxiliary/needless_borrow_include.in"));
+}
+
+#[allow(clippy::useless_vec)]
+fn iterator_chain_heads() {
+    use std::collections::BTreeSet;
+
+    let v = vec![1, 2, 3];
+    let _: Vec<_> = (&v).iter().rev().collect();
+    let _: Vec<i32> = (&v).iter().copied().filter(|x| *x > 1).map(|x| x * 2).rev().collect();
+    let _: Vec<_> = (&&v).iter().rev().collect();
+    let _ = (&v[..]).iter().rev().skip(1).count();
+
+    let s: BTreeSet<i32> = BTreeSet::new();
+    let _: Vec<_> = (&s).iter().rev().take(2).collect();
+    // Don't lint. `IntoIterator` is implemented for `&BTreeSet<_>`
+    #[allow(clippy::into_iter_on_ref)]
+    let _: Vec<_> = (&s).into_iter().rev().collect();
+}
+
+#[allow(clippy::into_iter_on_ref)]
+fn iter_receivers(mut v: Vec<i32>, rv: &Vec<i32>, a: [i32; 2]) {
+    let _ = (&v).iter();
+    let _ = (&mut v).iter_mut();
+    let _ = (&a).iter();
+    // `<&Vec<_> as IntoIterator>::into_iter` is called either way
+    let _ = (&rv).into_iter();
+
+    // Don't lint. `into_iter` would consume the value instead of the reference
+    let _ = (&v).into_iter();
+    let _ = (&mut v).into_iter();
+    let _ = (&a).into_iter();
+}
+
+#[allow(clippy::borrow_deref_ref)]
+fn move_closure_captures() {
+    let s = String::new();
+    let captured = &s;
+    let _ = move || &*captured;
+    let _ = move || -> &String { &*captured };
+    let _ = move || {
+        println!("{captured}");
+        &*captured
+    };
+
+    // Don't lint. `captured` is borrowed by the closure
+    let _ = || &*captured;
+    // Don't lint. Not the result of the closure
+    let _ = move || {
+        let _ = &*captured;
+    };
+    // Don't lint. Not a captured variable
+    let _ = move || {
+        let x = captured;
+        &*x
+    };
+}
+
+fn borrow_chains() {
+    macro_rules! m {
+        ($e:expr) => {
+            &$e
+        };
+    }
+    let a = 5;
+    let r = &&a;
+    // Only the borrows outside the macro are removed
+    let _ = x(&&&m!(a));
+    // The remaining borrow stops at the deref
+    let _ = x(&&&**r);
+}
+
+#[allow(clippy::useless_vec)]
+fn repeat_elements() {
+    let s = String::new();
+    let _: Vec<&String> = vec![&&s; 3];
+    let _: [&String; 3] = [&&s; 3];
+
+    // Don't lint. The element type is inferred from the element
+    let _ = vec![&&s; 3];
+    let _: [_; 3] = [&&s; 3];
+}
+
+fn outer_borrow_only() {
+    let a = 5;
+    let _ = x(&(&a));
+    let _ = x(&(&&a));
+    let _ = x(&&(a));
+    let _ = x(&mut (&a));
+}
+
+fn manually_drop() {
+    use std::mem::ManuallyDrop;
+
+    let md = ManuallyDrop::new(5);
+    let r = &md;
+    // Don't lint. Auto-deref goes through `ManuallyDrop`
+    let _ = x(&&md);
+    let _ = x(&r);
+    let _ = (&md).abs();
+}
+
+fn assert_macros() {
+    let a = String::new();
+    let b = String::new();
+    assert_eq!(&a, &b);
+    assert_ne!(&a, &"x");
+    debug_assert_eq!(&a, &b, "message");
+    debug_assert_ne!(&1, &2);
+    // The macro borrows its operands, so `a` and `b` aren't moved
+    let _ = a.len() + b.len();
+
+    // Don't lint. Only one of the operands is borrowed
+    assert_eq!(&a, "");
+    macro_rules! m {
+        ($e:expr) => {
+            &$e
+        };
+    }
+    // Don't lint. The borrow comes from a macro
+    assert_eq!(m!(a), &b);
+}
+
+fn significant_drop() {
+    use std::sync::{Mutex, MutexGuard};
+
+    let m = Mutex::new(String::new());
+    // Don't lint. The guard's lifetime is extended by the borrow
+    {
+        let g: &MutexGuard<'_, String> = &&m.lock().unwrap();
+        let _ = g.len();
+    }
+    {
+        let s: &String = &&*m.lock().unwrap();
+        let _ = s.len();
+    }
+
+    let _ = (&m.lock().unwrap()).len();
+    let _: &String = &&String::new();
+}
+
+fn unsize_coercions() {
+    use std::fmt::Display;
+
+    fn f(_: &dyn Display) {}
+    fn g(_: &mut dyn Display) {}
+
+    let s = String::new();
+    let r = &s;
+    // Don't lint. The references are unsized, not dereferenced
+    f(&s);
+    f(&&s);
+    f(&r);
+    g(&mut &s);
+    let _: &dyn Display = &&s;
+
+    // Method receivers are unsized after being borrowed again
+    let a = [0u8; 2];
+    let _ = (&&a).len();
+}
+
+fn tuple_elements() {
+    let a = String::new();
+    let _: (&String, u32) = (&&a, 0);
+    let _: ((&String, u32), &String) = ((&&a, 0), &&a);
+
+    // Don't lint. The tuple's type is inferred from the elements
+    let _ = (&&a, 0);
+    let _: (&&_, u32) = (&&a, 0);
+}
+
+fn interior_mutability() {
+    use std::cell::{Cell, RefCell};
+
+    fn f(_: &Cell<i32>) {}
+
+    let c = RefCell::new(String::new());
+    let b = Box::new(Cell::new(0));
+    // Don't lint. The cell's contents are accessed explicitly
+    let _ = (&*c.borrow()).len();
+    let _ = (&*c.borrow_mut()).len();
+    f(&&*b);
+
+    let cell = Cell::new(0);
+    f(&&cell);
+}
+
+fn compound_assign() {
+    let mut s = String::new();
+    let t = String::from("a");
+    s += &&t;
+
+    // Don't lint. `i32` implements `AddAssign` for both `i32` and `&i32`
+    let mut x = 0;
+    x += &1;
+}
+
+#[allow(clippy::op_ref)]
+fn binary_operands() {
+    use std::ops::Add;
+
+    #[derive(Clone, Copy)]
+    struct V(i32);
+    impl Add<&V> for &V {
+        type Output = V;
+        fn add(self, other: &V) -> V {
+            V(self.0 + other.0)
+        }
+    }
+
+    // Don't lint. `V` only implements `Add` through references
+    let a = V(1);
+    let b = V(2);
+    let _ = &a + &b;
+    let _ = &(&a + &b) + &b;
+    let r = &a;
+    let _ = r + &b;
+
+    // Don't lint. `op_ref` checks which `Add` impls exist
+    let _ = &1 + &2;
+    let s = String::new();
+    let _ = s + &String::from("a");
+}
+
+// `drop(&x)` is linted by rustc's `dropping_references`
+#[allow(dropping_references)]
+fn dropped_references(v: Vec<i32>, r: &Vec<i32>) {
+    // Don't lint. `drop` takes any type
+    drop(&v);
+    drop(&&v);
+    std::mem::drop(&**r);
+}
+
+#[allow(noop_method_call, suspicious_double_ref_op, clippy::redundant_clone)]
+fn clone_receivers() {
+    let s = String::new();
+    let _: String = (&s).clone();
+
+    // Don't lint. Removing the borrow would clone the `str` or `String` instead of the reference
+    let r: &str = "a";
+    let _: &str = (&r).clone();
+    let rs = &s;
+    let _: &String = (&rs).clone();
+}
+
+#[allow(clippy::needless_borrow, clippy::explicit_auto_deref, clippy::redundant_reborrow)]
+mod allowed {
+    fn f(_: &str) {}
+
+    fn allowed(x: &str) {
+        f(&x);
+        if let Some(ref y) = Some(x) {
+            f(y);
+        }
+    }
+
+    #[warn(clippy::needless_borrow)]
+    fn warned(x: &str) {
+        f(&x);
+        if let Some(ref y) = Some(x) {
+            f(y);
+        }
+    }
+}
+
+// The derive's output has the span of `FromDerive`, not of an expansion
+#[derive(proc_macro_derive::DerefInDerive)]
+struct FromDerive;
@@ -0,0 +1,34 @@
+#![warn(clippy::needless_borrow, clippy::explicit_auto_deref)]
+#![allow(clippy::borrow_deref_ref)]
+
+fn f(_: &String) {}
+fn f_str(_: &str) {}
+
+fn main() {
+    let s = String::new();
+    let x = &s;
+
+    // Left to `deref_addrof`, which removes the borrow along with the deref
+    f(&*&s);
+    f_str(&*&s);
+    let _: &str = &*&s;
+
+    // A single suggestion removes both the borrow and the derefs
+    f_str(&&**x);
+    let _: &str = &&**x;
+    // Calling `len` on `x` would use `String::len` instead of `str::len`
+    let _ = (&**x).len();
+    let _ = (&&**x).len();
+}
+
+#[allow(clippy::deref_addrof)]
+fn collapsed_chains() {
+    let s = String::new();
+    let r = &s;
+
+    f(&*&s);
+    f(&*&r);
+    f(&&*&s);
+    f_str(&&*&*r);
+    let _ = (&*&s).len();
+}
@@ -1,8 +1,12 @@
 // FIXME: run-rustfix waiting on multi-span suggestions
+//@aux-build:proc_macros.rs
 
 #![warn(clippy::needless_borrow)]
 #![allow(clippy::needless_borrowed_reference, clippy::explicit_auto_deref)]
 
+extern crate proc_macros;
+use proc_macros::external;
+
 fn f1(_: &str) {}
 macro_rules! m1 {
     ($e:expr) => {
@@ -54,6 +58,12 @@ fn main() {
         None => return,
     };
 
+    // Ok, the name and the rest of the pattern are from different expansions
+    let _: &String = match Some(&x) {
+        external!(Some(ref $x)) => x,
+        None => return,
+    };
+
     // Err, reference to a &String
     let _: &String = match Some(&x) {
         Some(ref x) => x,
@@ -110,6 +120,19 @@ fn main() {
         None => return,
     };
 
+    struct S {
+        a: u32,
+        b: String,
+    }
+    let s = S { a: 0, b: String::new() };
+    // Err, reference to a &S. Only used through field accesses, so only `ref` is removed.
+    if let Some(ref x) = Some(&s) {
+        //~^ ERROR: this pattern creates a reference to a reference
+        let _: u32 = x.a;
+        let _: &String = &x.b;
+        let _ = x.b.len();
+    }
+
     enum E {
         A(&'static u32),
         B(&'static u32),
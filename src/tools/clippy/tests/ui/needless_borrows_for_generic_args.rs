@@ -299,4 +299,91 @@ fn test() {
             check_str(&owner.0); // Don't lint. `owner` can't be partially moved because it impl Drop
         }
     }
+    // range-replacement iterator arguments
+    {
+        struct Buf<T>(Vec<T>);
+        impl<T: PartialEq> Buf<T> {
+            fn range_eq<I: IntoIterator<Item = T>>(&self, range: std::ops::Range<usize>, other: I) -> bool {
+                self.0[range].iter().zip(other).all(|(x, y)| *x == y)
+            }
+        }
+
+        let buf = Buf(vec![1, 2, 3]);
+        let mut iter = vec![4, 5].into_iter();
+        let _ = buf.range_eq(0..2, &mut iter);
+
+        // Don't lint. `iter` is used later
+        let mut iter = vec![6].into_iter();
+        let _ = buf.range_eq(0..1, &mut iter);
+        let _ = iter.next();
+
+        // Don't lint. Removing the borrow changes the item type
+        let x = 7;
+        let other = vec![x];
+        let refs = Buf(vec![&x]);
+        let _ = refs.range_eq(0..1, &other);
+
+        // Don't lint. The `Vec` may hold on to the borrow, and the iterator's type is part of the
+        // returned `Splice`
+        let mut v = vec![1, 2, 3];
+        let mut iter = vec![4, 5].into_iter();
+        let _ = v.splice(.., &mut iter);
+    }
+    {
+        struct Builder {
+            names: Vec<String>,
+        }
+        impl Builder {
+            fn new() -> Self {
+                Self { names: Vec::new() }
+            }
+            fn name(mut self, name: impl Into<String>) -> Self {
+                self.names.push(name.into());
+                self
+            }
+            fn path<P: AsRef<Path>>(self, path: P) -> Self {
+                let _ = path.as_ref();
+                self
+            }
+            fn cloned<S: Into<String> + Clone>(self, name: S) -> Self {
+                self.name(name)
+            }
+        }
+
+        let name = String::new();
+        let path = std::path::PathBuf::new();
+        let _ = Builder::new().name(&name).path(&path);
+
+        // Don't lint. `name` is used later
+        let name = String::new();
+        let _ = Builder::new().name(&name).name(&name);
+
+        // Don't lint. The type is given explicitly
+        let path = std::path::PathBuf::new();
+        let _ = Builder::new().path::<&std::path::PathBuf>(&path);
+
+        // Don't lint. `Clone::clone_from` takes `&mut self`
+        let name = String::new();
+        let _ = Builder::new().cloned(&name);
+    }
+    // `Extend` arguments
+    {
+        let other = vec![1u32, 2];
+        let mut v: Vec<u32> = Vec::new();
+        v.extend(&mut other.iter().copied());
+        let mut s = String::new();
+        s.extend(&mut "ab".chars().rev());
+
+        // Don't lint. `Extend<&T>` yields references, removing the borrow would extend with `T`
+        v.extend(&other);
+
+        // Don't lint. `iter` is used later
+        let mut iter = other.iter().copied();
+        v.extend(&mut iter);
+        let _ = iter.next();
+
+        // Don't lint. The `Vec` holds references, which may borrow from the iterator's source
+        let mut refs: Vec<&u32> = Vec::new();
+        refs.extend(&mut other.iter());
+    }
 }
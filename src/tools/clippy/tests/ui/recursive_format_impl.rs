@@ -2,6 +2,7 @@
 #![allow(
     clippy::borrow_deref_ref,
     clippy::deref_addrof,
+    clippy::explicit_auto_deref,
     clippy::inherent_to_string_shadow_display,
     clippy::to_string_in_format_args,
     clippy::uninlined_format_args
@@ -0,0 +1,93 @@
+#![warn(clippy::redundant_reborrow)]
+#![allow(
+    clippy::borrow_deref_ref,
+    clippy::explicit_auto_deref,
+    clippy::needless_borrow,
+    clippy::vec_init_then_push
+)]
+
+fn ret_ref<T: ?Sized>(x: &T) -> &T {
+    &*x
+}
+
+fn ret_mut<T: ?Sized>(x: &mut T) -> &mut T {
+    &mut *x
+}
+
+fn bindings<T: ?Sized>(x: &T, y: &mut T) {
+    let _: &T = &*x;
+    let _: &mut T = &mut *y;
+    // `y` is reborrowed by the coercion, so it can still be used here
+    let _: &mut T = &mut *y;
+}
+
+struct S<'a, T: ?Sized> {
+    x: &'a mut T,
+}
+impl<'a, T: ?Sized> S<'a, T> {
+    fn field(&mut self) -> &mut T {
+        &mut *self.x
+    }
+}
+
+fn sized<T>(x: &T, y: &mut T) {
+    let _: &T = &*x;
+    let _: &mut T = &mut *y;
+}
+
+// Don't lint. Not a type parameter, and not moved into a generic argument
+fn mut_args(x: &mut Vec<i32>, y: &mut i32) {
+    struct S;
+    impl S {
+        fn take(&mut self, _: &mut i32) {}
+    }
+    let mut s = S;
+    s.take(&mut *y);
+    s.take(&mut *y);
+    let _: &mut Vec<i32> = &mut *x;
+    x.push(0);
+}
+
+fn generic_args(x: &mut i32, y: &mut i32, z: &mut i32) {
+    fn take<T>(_: T) {}
+
+    // The argument type is known, so `x` is reborrowed
+    let mut v: Vec<&mut i32> = Vec::new();
+    v.push(&mut *x);
+    drop(v);
+    *x = 0;
+
+    // The argument type is inferred, so `y` is moved
+    take(&mut *y);
+    // Don't lint. `z` is used again, removing the reborrow would move it
+    take(&mut *z);
+    *z = 0;
+}
+
+// Don't lint. Not a type parameter, and not moved into a generic argument
+fn non_null(mut p: std::ptr::NonNull<String>) {
+    let _: &mut String = unsafe { &mut *p.as_mut() };
+}
+
+// Don't lint
+fn no_lint<T: ?Sized>(x: &mut T, y: &&T, z: &String) {
+    // Changes the mutability of the reference
+    let _: &T = &*x;
+    // Not a no-op. `y` is a reference to a reference
+    let _: &T = &**y;
+    // Not a type parameter
+    let _: &String = &*z;
+    // The type is inferred from the reborrow
+    let _ = &mut *x;
+    let _ = &*x;
+}
+
+#[allow(clippy::redundant_reborrow)]
+fn borrow_deref_ref<T: ?Sized>(x: &T) -> &T {
+    // Immutable reborrows are only linted when `borrow_deref_ref` is allowed
+    #[warn(clippy::borrow_deref_ref)]
+    #[warn(clippy::redundant_reborrow)]
+    &*x
+}
+
+fn main() {}
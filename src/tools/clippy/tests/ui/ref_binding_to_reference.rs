@@ -52,6 +52,18 @@ fn main() {
         None => return,
     }
 
+    // Err, reference to a &(String, u32). No reference is added to the field accesses.
+    let y = (String::new(), 0);
+    let _: &&(String, u32) = match Some(&y) {
+        Some(ref x) => {
+            //~^ ERROR: this pattern creates a reference to a reference
+            f1(&x.0);
+            let _: u32 = x.1;
+            x
+        },
+        None => return,
+    };
+
     // Err, reference to a &String
     let _ = |&ref x: &&String| {
         //~^ ERROR: this pattern creates a reference to a reference
@@ -91,3 +103,58 @@ fn check_expect_suppression() {
         None => return,
     };
 }
+
+// The usages are only changed by the ref binding's suggestion
+#[warn(clippy::explicit_auto_deref, clippy::needless_borrow)]
+fn auto_deref_usages() {
+    let x = String::new();
+    if let Some(ref x) = Some(&x) {
+        //~^ ERROR: this pattern creates a reference to a reference
+        f1(&**x);
+        let _: &String = &**x;
+    }
+    if let Some(ref x) = Some(&x) {
+        //~^ ERROR: this pattern creates a reference to a reference
+        f1(&***x);
+        let _: &&String = x;
+    }
+}
+
+#[warn(clippy::explicit_auto_deref)]
+fn auto_deref_usages_allowed() {
+    let x = String::new();
+    #[allow(clippy::needless_borrow)]
+    if let Some(ref x) = Some(&x) {
+        f1(&**x);
+        //~^ ERROR: deref which would be done by auto-deref
+    }
+}
+
+fn closure_captures() {
+    let x = String::new();
+    if let Some(ref x) = Some(&x) {
+        //~^ ERROR: this pattern creates a reference to a reference
+        let c = || {
+            let _: &String = *x;
+        };
+        c();
+    }
+    if let Some(ref x) = Some(&x) {
+        //~^ ERROR: this pattern creates a reference to a reference
+        let c = move || {
+            let _: &String = *x;
+        };
+        c();
+    }
+    if let Some(ref x) = Some(&x) {
+        //~^ ERROR: this pattern creates a reference to a reference
+        let c = || x;
+        let _: &&String = c();
+    }
+
+    // Don't lint. The closure's copy of the binding would be borrowed
+    if let Some(ref x) = Some(&x) {
+        let c = move || x;
+        let _: &&String = c();
+    }
+}
@@ -4,7 +4,7 @@
 use proc_macros::external;
 
 #[warn(clippy::string_add)]
-#[allow(clippy::string_add_assign, unused)]
+#[allow(clippy::string_add_assign, clippy::needless_borrow, unused)]
 fn main() {
     // ignores assignment distinction
     let mut x = String::new();
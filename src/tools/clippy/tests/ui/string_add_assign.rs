@@ -1,4 +1,4 @@
-#[allow(clippy::string_add, unused)]
+#[allow(clippy::string_add, clippy::needless_borrow, unused)]
 #[warn(clippy::string_add_assign)]
 fn main() {
     // ignores assignment distinction